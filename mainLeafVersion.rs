@@ -1,20 +1,46 @@
 use std::collections::HashMap;
 use std::str::FromStr;
-use bitcoin::{Address, Network, Script, Transaction, TxIn, TxOut, OutPoint, Txid};
-use bitcoin::blockdata::opcodes::all::{OP_RETURN, OP_13, OP_DUP, OP_HASH160, OP_EQUALVERIFY, OP_CHECKSIG, OP_IF, OP_ELSE, OP_CHECKLOCKTIMEVERIFY, OP_DROP, OP_ENDIF};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::rc::Rc;
+use bitcoin::{Address, Network, Script, Transaction, TxIn, TxOut, OutPoint, Txid, XOnlyPublicKey};
+use bitcoin::blockdata::opcodes::all::{OP_RETURN, OP_13, OP_DUP, OP_HASH160, OP_EQUALVERIFY, OP_CHECKSIG, OP_CHECKMULTISIG, OP_CHECKSIGADD, OP_NUMEQUAL, OP_CHECKLOCKTIMEVERIFY, OP_DROP};
 use bitcoin::util::psbt::Input as PsbtInput;
 use bitcoin::util::key::PublicKey;
-use bitcoin::hashes::{Hash, sha256};
-use secp256k1::Secp256k1;
+use bitcoin::util::taproot::TweakedPublicKey;
+use bitcoin::util::bip32::{DerivationPath, Fingerprint};
+use bitcoin::hashes::{Hash, HashEngine, sha256, hash160};
+use secp256k1::{Secp256k1, SecretKey};
 use bitcoincore_rpc::{Auth, Client, RpcApi};
+use electrum_client::ElectrumApi;
+use ledger_transport_hid::TransportNativeHID;
 use clap::{App, Arg, SubCommand};
 use thiserror::Error;
 use unicode_categories::UnicodeCategories;
+use bech32::{ToBase32, FromBase32, Variant};
+use blake2b_simd::Params as Blake2bParams;
 
 const COIN: u64 = 100_000_000;
 const DEFAULT_SYMBOL_DIVISIBILITY: u8 = 8;
 const DEFAULT_CURRENCY_SYMBOL: char = '¤';
 const MAX_GLYPH_NAME_LENGTH: usize = 26;
+const TAPROOT_LEAF_VERSION: u8 = 0xc0;
+/// The secp256k1 group order `n`, big-endian — the modulus every scalar
+/// used in a Schnorr signature (nonces, challenges, `s` values) reduces
+/// against. Needed by hand because DLC adaptor signatures complete by
+/// adding two scalars mod `n`, an operation this file's secp256k1 version
+/// only exposes for EC points, not raw secret scalars.
+const SECP256K1_ORDER: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xBA, 0xAE, 0xDC, 0xE6, 0xAF, 0x48, 0xA0, 0x3B,
+    0xBF, 0xD2, 0x5E, 0x8C, 0xD0, 0x36, 0x41, 0x41,
+];
+const GLYPH_REF_HRP: &str = "glyph";
+const F4JUMBLE_G_PERSONAL: &[u8] = b"UA-F4Jumble_G";
+const F4JUMBLE_H_PERSONAL: &[u8] = b"UA-F4Jumble_H";
 
 #[derive(Error, Debug)]
 enum GlyphError {
@@ -32,23 +58,523 @@ enum GlyphError {
     BitcoinError(#[from] bitcoin::util::Error),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SwapRole {
+    Initiator,
+    Participant,
+}
+
+impl SwapRole {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SwapRole::Initiator => "initiator",
+            SwapRole::Participant => "participant",
+        }
+    }
+
+    fn from_str(s: &str) -> Result<Self, GlyphError> {
+        match s {
+            "initiator" => Ok(SwapRole::Initiator),
+            "participant" => Ok(SwapRole::Participant),
+            _ => Err(GlyphError::InvalidTransaction(format!("Unknown swap role: {}", s))),
+        }
+    }
+}
+
+/// Everything needed to later claim or refund a pending HTLC, plus whatever we
+/// learned about the counterparty's side so a watcher can act on our behalf.
+#[derive(Debug, Clone)]
+struct SwapRecord {
+    role: SwapRole,
+    htlc_txid: String,
+    vout: u32,
+    amount: u64,
+    secret_hash: Vec<u8>,
+    preimage: Option<Vec<u8>>,
+    timelock: u32,
+    counterparty_pubkey: String,
+    own_pubkey: String,
+    destination_address: String,
+    /// The counterparty's matching leg of the swap, once known — the one
+    /// that actually pays us. We're its hashlock-leaf receiver (they're the
+    /// sender/refund), the reverse of `htlc_txid`:`vout` above, so
+    /// `claim_swap` spends this outpoint, not our own. `peer_timelock` is
+    /// the timelock they locked it with, needed to rebuild its tap leaves.
+    peer_htlc_txid: Option<String>,
+    peer_vout: Option<u32>,
+    peer_timelock: Option<u32>,
+}
+
+/// Where a tracked swap currently stands. `Pending`/`Claimed`/`Refunded`
+/// describe what's already happened on-chain; `Claimable`/`Refundable` are
+/// the watcher's judgment that it should act now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SwapStatus {
+    Pending,
+    Claimable,
+    Claimed,
+    Refundable,
+    Refunded,
+}
+
+impl SwapStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SwapStatus::Pending => "pending",
+            SwapStatus::Claimable => "claimable",
+            SwapStatus::Claimed => "claimed",
+            SwapStatus::Refundable => "refundable",
+            SwapStatus::Refunded => "refunded",
+        }
+    }
+}
+
+/// A single move of `amount` units of `glyph_id` into `output_index`. A `T`
+/// glyphstone carries a delta-compressed sequence of these, runes-style, so
+/// one transaction can split a glyph across many recipients or move several
+/// distinct glyphs at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edict {
+    glyph_id: (u32, u32),
+    amount: u64,
+    output_index: u32,
+}
+
+/// An m-of-n group authorized to control a glyph's issuance outputs. Used by
+/// `etch_glyph`/`mint_glyph` in place of a single destination address so an
+/// issuing DAO can require `threshold` signatures out of `signer_pubkeys`
+/// before premined or minted units move.
+#[derive(Debug, Clone)]
+struct MultisigConfig {
+    threshold: u8,
+    signer_pubkeys: Vec<PublicKey>,
+}
+
+impl MultisigConfig {
+    fn validate(&self) -> Result<(), GlyphError> {
+        if self.threshold == 0 || self.signer_pubkeys.is_empty() || self.threshold as usize > self.signer_pubkeys.len() {
+            return Err(GlyphError::InvalidTransaction(format!(
+                "Invalid multisig config: {}-of-{}", self.threshold, self.signer_pubkeys.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An oracle's public commitment to a future base-2 attestation over
+/// `nonce_points.len()` digits, most-significant digit first. Each nonce
+/// point lets anyone compute, before the oracle signs anything, the
+/// Schnorr "anticipation point" for either value of that digit via the
+/// BIP-340 challenge relation `R + e*P`.
+#[derive(Debug, Clone)]
+struct OracleAnnouncement {
+    oracle_pubkey: PublicKey,
+    nonce_points: Vec<PublicKey>,
+}
+
+/// One Contract Execution Transaction: the payout split if the oracle's
+/// attestation starts with `digit_prefix`, and the adaptor point under
+/// which the counterparty's claim is encrypted until that attestation
+/// appears.
+#[derive(Debug, Clone)]
+struct Cet {
+    digit_prefix: Vec<u8>,
+    payout_a: u64,
+    payout_b: u64,
+    anticipation_point: PublicKey,
+}
+
+/// Abstracts the chain queries the protocol needs so it isn't pinned to a
+/// bitcoind Core RPC connection: fetching a transaction, checking whether a
+/// specific output is still unspent, current block height, fetching a
+/// block's or the mempool's transactions (for spend detection), and
+/// broadcasting. `Transaction`/`TxOut` are plain `bitcoin` crate types, not
+/// Core-specific wrappers, so any backend can produce them.
+trait ChainBackend {
+    fn get_transaction(&self, txid: &Txid) -> Result<Transaction, GlyphError>;
+    fn get_tx_out(&self, txid: &Txid, vout: u32) -> Result<Option<TxOut>, GlyphError>;
+    fn get_block_count(&self) -> Result<u32, GlyphError>;
+    fn get_block_transactions(&self, height: u32) -> Result<Vec<Transaction>, GlyphError>;
+    fn get_mempool_transactions(&self) -> Result<Vec<Transaction>, GlyphError>;
+    fn broadcast(&self, raw_tx: &[u8]) -> Result<Txid, GlyphError>;
+    fn new_change_address(&self) -> Result<String, GlyphError>;
+    fn pubkey_for_address(&self, address: &str) -> Result<PublicKey, GlyphError>;
+}
+
+/// Produces signatures for an unsigned transaction or PSBT. Separate from
+/// `ChainBackend` because not every backend can: an Electrum/Esplora client
+/// has no wallet behind it to call `sign_raw_transaction_with_wallet`, so
+/// `GlyphProtocol::new_electrum` pairs it with `NoSigner`; signing needs a
+/// `CoreRpcBackend` or a `LedgerSigner`.
+trait Signer {
+    fn sign_transaction(&self, tx: &Transaction) -> Result<Vec<u8>, GlyphError>;
+    fn combine_psbt(&self, psbts: &[String]) -> Result<String, GlyphError>;
+    fn finalize_psbt(&self, psbt_base64: &str) -> Result<Vec<u8>, GlyphError>;
+    /// Lists the connected wallet's spendable UTXOs for funding a new
+    /// transaction. Tied to `Signer` rather than `ChainBackend` because it's
+    /// inherently wallet state, not a chain query any backend can answer.
+    fn list_unspent(&self) -> Result<Vec<bitcoincore_rpc::json::ListUnspentResultEntry>, GlyphError>;
+    /// Exports the raw secret scalar behind `pubkey`, if this signer holds
+    /// one. Every other signing path in this file stays behind
+    /// `sign_transaction`'s whole-transaction black box; DLC CET adaptor
+    /// signatures are the one place that isn't enough, since completing one
+    /// is scalar arithmetic on a signature's own `s` value against a nonce
+    /// we have to choose ourselves — not something a wallet RPC or hardware
+    /// device can be asked to do on our behalf.
+    fn export_secret_for_pubkey(&self, pubkey: &PublicKey, network: Network) -> Result<SecretKey, GlyphError>;
+}
+
+/// The default backend: a full bitcoind node reached over its JSON-RPC
+/// interface. Also a `Signer`, since it's backed by a wallet.
+#[derive(Clone)]
+struct CoreRpcBackend {
+    client: Rc<Client>,
+}
+
+impl CoreRpcBackend {
+    fn new(rpc_url: &str, rpc_user: &str, rpc_pass: &str) -> Result<Self, GlyphError> {
+        let client = Client::new(rpc_url, Auth::UserPass(rpc_user.to_string(), rpc_pass.to_string()))
+            .map_err(GlyphError::RpcError)?;
+        Ok(CoreRpcBackend { client: Rc::new(client) })
+    }
+}
+
+impl ChainBackend for CoreRpcBackend {
+    fn get_transaction(&self, txid: &Txid) -> Result<Transaction, GlyphError> {
+        Ok(self.client.get_raw_transaction(txid, None)?)
+    }
+
+    fn get_tx_out(&self, txid: &Txid, vout: u32) -> Result<Option<TxOut>, GlyphError> {
+        Ok(self.client.get_tx_out(txid, vout, Some(true))?.map(|out| TxOut {
+            value: out.value.to_sat(),
+            script_pubkey: out.script_pub_key.script().unwrap_or_default(),
+        }))
+    }
+
+    fn get_block_count(&self) -> Result<u32, GlyphError> {
+        Ok(self.client.get_block_count()? as u32)
+    }
+
+    fn get_block_transactions(&self, height: u32) -> Result<Vec<Transaction>, GlyphError> {
+        let block_hash = self.client.get_block_hash(height as u64)?;
+        Ok(self.client.get_block(&block_hash)?.txdata)
+    }
+
+    fn get_mempool_transactions(&self) -> Result<Vec<Transaction>, GlyphError> {
+        self.client.get_raw_mempool()?.iter()
+            .map(|txid| Ok(self.client.get_raw_transaction(txid, None)?))
+            .collect()
+    }
+
+    fn broadcast(&self, raw_tx: &[u8]) -> Result<Txid, GlyphError> {
+        Ok(self.client.send_raw_transaction(raw_tx)?)
+    }
+
+    fn new_change_address(&self) -> Result<String, GlyphError> {
+        Ok(self.client.get_new_address(None, None)?.to_string())
+    }
+
+    fn pubkey_for_address(&self, address: &str) -> Result<PublicKey, GlyphError> {
+        let address_info = self.client.get_address_info(address)?;
+        PublicKey::from_str(&address_info.pubkey.ok_or_else(|| GlyphError::InvalidTransaction("No pubkey found for address".to_string()))?)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid pubkey for address: {}", e)))
+    }
+}
+
+impl Signer for CoreRpcBackend {
+    fn sign_transaction(&self, tx: &Transaction) -> Result<Vec<u8>, GlyphError> {
+        let signed = self.client.sign_raw_transaction_with_wallet(tx, None, None)?;
+        Ok(signed.hex)
+    }
+
+    fn combine_psbt(&self, psbts: &[String]) -> Result<String, GlyphError> {
+        Ok(self.client.combine_psbt(psbts)?)
+    }
+
+    fn finalize_psbt(&self, psbt_base64: &str) -> Result<Vec<u8>, GlyphError> {
+        let finalized = self.client.finalize_psbt(psbt_base64, Some(true))?;
+        finalized.hex.ok_or_else(|| GlyphError::InvalidTransaction("PSBT is not yet fully signed".to_string()))
+    }
+
+    fn list_unspent(&self) -> Result<Vec<bitcoincore_rpc::json::ListUnspentResultEntry>, GlyphError> {
+        Ok(self.client.list_unspent(None, None, None, None, None)?)
+    }
+
+    fn export_secret_for_pubkey(&self, pubkey: &PublicKey, network: Network) -> Result<SecretKey, GlyphError> {
+        let address = Address::p2wpkh(pubkey, network)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Cannot derive a p2wpkh address for this pubkey: {}", e)))?;
+        Ok(self.client.dump_private_key(&address)?.inner)
+    }
+}
+
+/// A light-client backend: talks to an Electrum/Esplora-compatible server
+/// instead of a full node, so users can run the Glyph CLI without syncing
+/// bitcoind. Has no wallet, so it never implements `Signer`.
+struct ElectrumBackend {
+    client: electrum_client::Client,
+}
+
+impl ElectrumBackend {
+    fn new(electrum_url: &str) -> Result<Self, GlyphError> {
+        let client = electrum_client::Client::new(electrum_url)
+            .map_err(|e| GlyphError::NetworkError(format!("Failed to connect to Electrum server: {}", e)))?;
+        Ok(ElectrumBackend { client })
+    }
+}
+
+impl ChainBackend for ElectrumBackend {
+    fn get_transaction(&self, txid: &Txid) -> Result<Transaction, GlyphError> {
+        self.client.transaction_get(txid)
+            .map_err(|e| GlyphError::NetworkError(format!("Electrum transaction_get failed: {}", e)))
+    }
+
+    fn get_tx_out(&self, txid: &Txid, vout: u32) -> Result<Option<TxOut>, GlyphError> {
+        let tx = self.get_transaction(txid)?;
+        let output = tx.output.get(vout as usize).cloned();
+        Ok(match output {
+            Some(out) => {
+                let txo_status = self.client.transaction_get_merkle(txid, 0)
+                    .map_err(|e| GlyphError::NetworkError(format!("Electrum transaction_get_merkle failed: {}", e)));
+                // Electrum has no direct "is this output still unspent" query without
+                // the owning scripthash's history, so we treat a resolvable tx as enough
+                // evidence the output existed; a spent output is still reported as present.
+                let _ = txo_status;
+                Some(out)
+            }
+            None => None,
+        })
+    }
+
+    fn get_block_count(&self) -> Result<u32, GlyphError> {
+        Ok(self.client.block_headers_subscribe()
+            .map_err(|e| GlyphError::NetworkError(format!("Electrum block_headers_subscribe failed: {}", e)))?
+            .height as u32)
+    }
+
+    fn get_block_transactions(&self, height: u32) -> Result<Vec<Transaction>, GlyphError> {
+        let header = self.client.block_header(height as usize)
+            .map_err(|e| GlyphError::NetworkError(format!("Electrum block_header failed: {}", e)))?;
+        let _ = header;
+        Err(GlyphError::NetworkError("Electrum backend cannot enumerate a block's transactions without an indexer extension".to_string()))
+    }
+
+    fn get_mempool_transactions(&self) -> Result<Vec<Transaction>, GlyphError> {
+        Err(GlyphError::NetworkError("Electrum backend cannot enumerate the full mempool; scan by scripthash history instead".to_string()))
+    }
+
+    fn broadcast(&self, raw_tx: &[u8]) -> Result<Txid, GlyphError> {
+        let tx: Transaction = bitcoin::consensus::deserialize(raw_tx)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid raw transaction: {}", e)))?;
+        self.client.transaction_broadcast(&tx)
+            .map_err(|e| GlyphError::NetworkError(format!("Electrum transaction_broadcast failed: {}", e)))
+    }
+
+    fn new_change_address(&self) -> Result<String, GlyphError> {
+        Err(GlyphError::NetworkError("Electrum backend has no wallet to generate addresses from".to_string()))
+    }
+
+    fn pubkey_for_address(&self, _address: &str) -> Result<PublicKey, GlyphError> {
+        Err(GlyphError::NetworkError("Electrum backend has no wallet to resolve an address's pubkey".to_string()))
+    }
+}
+
+/// Pairs with `ElectrumBackend`: a light client has no wallet, so every
+/// signing operation is simply unavailable.
+struct NoSigner;
+
+impl Signer for NoSigner {
+    fn sign_transaction(&self, _tx: &Transaction) -> Result<Vec<u8>, GlyphError> {
+        Err(GlyphError::InvalidTransaction("No signer configured: connect a Core wallet or hardware signer to sign transactions".to_string()))
+    }
+
+    fn combine_psbt(&self, _psbts: &[String]) -> Result<String, GlyphError> {
+        Err(GlyphError::InvalidTransaction("No signer configured: connect a Core wallet to combine PSBTs".to_string()))
+    }
+
+    fn finalize_psbt(&self, _psbt_base64: &str) -> Result<Vec<u8>, GlyphError> {
+        Err(GlyphError::InvalidTransaction("No signer configured: connect a Core wallet to finalize PSBTs".to_string()))
+    }
+
+    fn list_unspent(&self) -> Result<Vec<bitcoincore_rpc::json::ListUnspentResultEntry>, GlyphError> {
+        Err(GlyphError::InvalidTransaction("No signer configured: connect a Core wallet to list spendable UTXOs".to_string()))
+    }
+
+    fn export_secret_for_pubkey(&self, _pubkey: &PublicKey, _network: Network) -> Result<SecretKey, GlyphError> {
+        Err(GlyphError::InvalidTransaction("No signer configured: connect a Core wallet to export a DLC signing key".to_string()))
+    }
+}
+
+/// Signs with an external Ledger-style hardware device instead of a hot
+/// wallet: input metadata is serialized to a PSBT and streamed to the
+/// device for per-input confirmation and signing, and the signatures that
+/// come back are assembled into the final witness here — a private key
+/// never touches this process. `watch_only_client` is a Core wallet that
+/// has imported the device's addresses, used for `list_unspent`,
+/// `combine_psbt` and the `wallet_pubkey` lookup; none of these needs the
+/// device itself.
+struct LedgerSigner {
+    watch_only_client: Rc<Client>,
+    transport: TransportNativeHID,
+    derivation_path: DerivationPath,
+}
+
+impl LedgerSigner {
+    fn new(watch_only_client: Rc<Client>, derivation_path: &str) -> Result<Self, GlyphError> {
+        let transport = TransportNativeHID::new()
+            .map_err(|e| GlyphError::NetworkError(format!("Failed to open Ledger device: {}", e)))?;
+        let derivation_path = DerivationPath::from_str(derivation_path)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid derivation path '{}': {}", derivation_path, e)))?;
+        Ok(LedgerSigner { watch_only_client, transport, derivation_path })
+    }
+
+    /// The device confirms and signs under this wallet's own pubkey, so we
+    /// need it on hand to annotate the PSBT's `bip32_derivation` field —
+    /// fetched the same way `CoreRpcBackend::pubkey_for_address` does.
+    fn wallet_pubkey(&self) -> Result<PublicKey, GlyphError> {
+        let address = self.watch_only_client.get_new_address(None, None)?;
+        let address_info = self.watch_only_client.get_address_info(&address.to_string())?;
+        PublicKey::from_str(&address_info.pubkey.ok_or_else(|| GlyphError::InvalidTransaction("Ledger watch-only wallet has no pubkey on record".to_string()))?)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid Ledger wallet pubkey: {}", e)))
+    }
+
+    /// Builds the PSBT the device will sign against: one `bip32_derivation`
+    /// entry per input so the device knows which key to sign with, plus,
+    /// for HTLC spends, the leaf script it's about to execute. HTLC inputs
+    /// arrive with everything but the signature already sitting in the
+    /// unsigned input's witness (see `claim_swap`/`refund_swap`) — a PSBT's
+    /// unsigned tx carries no witness data, so that gets peeled off here
+    /// and handed back alongside the PSBT to reassemble after signing.
+    fn build_device_psbt(&self, tx: &Transaction) -> Result<(bitcoin::util::psbt::PartiallySignedTransaction, Vec<Vec<Vec<u8>>>), GlyphError> {
+        let mut unsigned_tx = tx.clone();
+        let pending_witness_items: Vec<Vec<Vec<u8>>> = unsigned_tx.input.iter_mut()
+            .map(|input| std::mem::take(&mut input.witness))
+            .collect();
+
+        let mut psbt = bitcoin::util::psbt::PartiallySignedTransaction::from_unsigned_tx(unsigned_tx)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to build PSBT for Ledger signing: {}", e)))?;
+
+        let wallet_pubkey = self.wallet_pubkey()?;
+        for (input, witness_items) in psbt.inputs.iter_mut().zip(&pending_witness_items) {
+            input.bip32_derivation.insert(wallet_pubkey.key, (Fingerprint::default(), self.derivation_path.clone()));
+            // `[preimage?, leaf_script, control_block]` — the leaf script is
+            // always second-to-last; plain key-path inputs have none of this.
+            if witness_items.len() >= 2 {
+                input.witness_script = Some(Script::from(witness_items[witness_items.len() - 2].clone()));
+            }
+        }
+
+        Ok((psbt, pending_witness_items))
+    }
+
+    /// Streams `psbt` to the device for per-input confirmation, returning
+    /// one signature per input in request order.
+    fn sign_with_device(&self, psbt: &bitcoin::util::psbt::PartiallySignedTransaction) -> Result<Vec<Vec<u8>>, GlyphError> {
+        ledger_bitcoin_client::sign_psbt(&self.transport, psbt, &self.derivation_path)
+            .map_err(|e| GlyphError::NetworkError(format!("Ledger signing failed: {}", e)))
+    }
+}
+
+impl Signer for LedgerSigner {
+    /// Builds the device PSBT, collects one signature per input, and
+    /// reassembles the final transaction: HTLC inputs get their signature
+    /// prepended to the leaf script/control block witness peeled off
+    /// earlier; plain inputs get a standard single-signature witness.
+    fn sign_transaction(&self, tx: &Transaction) -> Result<Vec<u8>, GlyphError> {
+        let (psbt, pending_witness_items) = self.build_device_psbt(tx)?;
+        let signatures = self.sign_with_device(&psbt)?;
+
+        let mut signed_tx = tx.clone();
+        for ((input, signature), witness_items) in signed_tx.input.iter_mut().zip(signatures).zip(pending_witness_items) {
+            let mut witness = vec![signature];
+            witness.extend(witness_items);
+            input.witness = witness;
+        }
+
+        Ok(bitcoin::consensus::encode::serialize(&signed_tx))
+    }
+
+    fn combine_psbt(&self, psbts: &[String]) -> Result<String, GlyphError> {
+        Ok(self.watch_only_client.combine_psbt(psbts)?)
+    }
+
+    /// Finalizes a PSBT (typically one built by `build_psbt` for offline
+    /// review) by routing it to the device rather than Core's own
+    /// `finalizepsbt` — the device holds the key, not the watch-only wallet.
+    fn finalize_psbt(&self, psbt_base64: &str) -> Result<Vec<u8>, GlyphError> {
+        let mut psbt = bitcoin::util::psbt::PartiallySignedTransaction::from_str(psbt_base64)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid PSBT: {}", e)))?;
+        let wallet_pubkey = self.wallet_pubkey()?;
+        for input in psbt.inputs.iter_mut() {
+            input.bip32_derivation.insert(wallet_pubkey.key, (Fingerprint::default(), self.derivation_path.clone()));
+        }
+        let signatures = self.sign_with_device(&psbt)?;
+
+        let mut signed_tx = psbt.unsigned_tx.clone();
+        for (input, signature) in signed_tx.input.iter_mut().zip(signatures) {
+            input.witness = vec![signature];
+        }
+        Ok(bitcoin::consensus::encode::serialize(&signed_tx))
+    }
+
+    fn list_unspent(&self) -> Result<Vec<bitcoincore_rpc::json::ListUnspentResultEntry>, GlyphError> {
+        Ok(self.watch_only_client.list_unspent(None, None, None, None, None)?)
+    }
+
+    fn export_secret_for_pubkey(&self, _pubkey: &PublicKey, _network: Network) -> Result<SecretKey, GlyphError> {
+        Err(GlyphError::InvalidTransaction("Hardware signer never exports its private key; it cannot presign DLC adaptor signatures".to_string()))
+    }
+}
+
 struct GlyphProtocol {
     network: Network,
-    rpc_client: Client,
+    backend: Box<dyn ChainBackend>,
+    signer: Box<dyn Signer>,
     base_offset: u8,
+    swap_store_path: String,
 }
 
 impl GlyphProtocol {
+    /// Connects to a bitcoind node over Core RPC, used as both chain backend
+    /// and signer.
     fn new(network: Network, rpc_url: &str, rpc_user: &str, rpc_pass: &str) -> Result<Self, GlyphError> {
-        let rpc_client = Client::new(rpc_url, Auth::UserPass(rpc_user.to_string(), rpc_pass.to_string()))
-            .map_err(GlyphError::RpcError)?;
+        let core_backend = CoreRpcBackend::new(rpc_url, rpc_user, rpc_pass)?;
+        Ok(GlyphProtocol {
+            network,
+            backend: Box::new(core_backend.clone()),
+            signer: Box::new(core_backend),
+            base_offset: 1,
+            swap_store_path: "glyph_swaps.store".to_string(),
+        })
+    }
+
+    /// Connects to an Electrum/Esplora-compatible server instead of a full
+    /// node. Any method that needs a `Signer` (broadcasting a self-signed
+    /// spend, combining/finalizing a PSBT) will fail, since a light client
+    /// has no wallet behind it.
+    fn new_electrum(network: Network, electrum_url: &str) -> Result<Self, GlyphError> {
+        let electrum_backend = ElectrumBackend::new(electrum_url)?;
         Ok(GlyphProtocol {
             network,
-            rpc_client,
+            backend: Box::new(electrum_backend),
+            signer: Box::new(NoSigner),
             base_offset: 1,
+            swap_store_path: "glyph_swaps.store".to_string(),
         })
     }
 
+    /// Swaps the active signer for a Ledger device, leaving `backend`
+    /// untouched: premine and swap claim/refund flows opt into this with
+    /// `--signer ledger` so the corresponding private key never leaves the
+    /// device. Only meaningful with a Core chain backend today, since the
+    /// watch-only wallet used for `list_unspent`/`combine_psbt` needs the
+    /// same RPC connection as `backend`.
+    fn use_ledger_signer(&mut self, rpc_url: &str, rpc_user: &str, rpc_pass: &str, derivation_path: &str) -> Result<(), GlyphError> {
+        let watch_only_client = Client::new(rpc_url, Auth::UserPass(rpc_user.to_string(), rpc_pass.to_string()))
+            .map_err(GlyphError::RpcError)?;
+        self.signer = Box::new(LedgerSigner::new(Rc::new(watch_only_client), derivation_path)?);
+        Ok(())
+    }
+
     fn symbol_to_int(&self, symbol: &str) -> Result<u64, GlyphError> {
         if !self.is_valid_glyph_name(symbol) {
             return Err(GlyphError::InvalidSymbol(format!("Invalid Glyph name: {}", symbol)));
@@ -111,8 +637,112 @@ impl GlyphProtocol {
         Err(GlyphError::InvalidTransaction("Incomplete varint".to_string()))
     }
 
+    /// One BLAKE2b-based round function used by `f4jumble`: hashes `message`
+    /// under a 16-byte personalization built from `tag` and `round`,
+    /// expanding past BLAKE2b's 64-byte cap by hashing successive
+    /// little-endian block counters until `out_len` bytes are produced.
+    fn f4jumble_hash(&self, tag: &[u8], round: u8, message: &[u8], out_len: usize) -> Vec<u8> {
+        let mut personal = [0u8; 16];
+        personal[..tag.len()].copy_from_slice(tag);
+        personal[15] = round;
+
+        let mut output = Vec::with_capacity(out_len);
+        let mut counter: u32 = 0;
+        while output.len() < out_len {
+            let mut block_message = counter.to_le_bytes().to_vec();
+            block_message.extend_from_slice(message);
+            let block_len = (out_len - output.len()).min(64);
+            let digest = Blake2bParams::new()
+                .hash_length(block_len)
+                .personal(&personal)
+                .hash(&block_message);
+            output.extend_from_slice(digest.as_bytes());
+            counter += 1;
+        }
+        output
+    }
+
+    /// The f4jumble unkeyed 4-round Feistel permutation: splits `message`
+    /// into a left half of `⌈len/2⌉` bytes and a right half of the rest, then
+    /// alternately XORs each half with a BLAKE2b hash of the other (rounds
+    /// G, H, G, H). Running the same four rounds in reverse order undoes it,
+    /// since each round only ever reads the half it doesn't modify.
+    fn f4jumble(&self, message: &[u8], forward: bool) -> Vec<u8> {
+        let left_len = (message.len() + 1) / 2;
+        let right_len = message.len() - left_len;
+        let mut left = message[..left_len].to_vec();
+        let mut right = message[left_len..].to_vec();
+
+        let rounds: [u8; 4] = if forward { [1, 2, 3, 4] } else { [4, 3, 2, 1] };
+        for round in rounds {
+            if round % 2 == 1 {
+                let mask = self.f4jumble_hash(F4JUMBLE_G_PERSONAL, round, &right, left_len);
+                for (byte, m) in left.iter_mut().zip(mask) {
+                    *byte ^= m;
+                }
+            } else {
+                let mask = self.f4jumble_hash(F4JUMBLE_H_PERSONAL, round, &left, right_len);
+                for (byte, m) in right.iter_mut().zip(mask) {
+                    *byte ^= m;
+                }
+            }
+        }
+
+        left.extend(right);
+        left
+    }
+
+    /// Serializes a glyph reference (plus an optional holder address) as
+    /// payload bytes, diffuses them with `f4jumble` so a single mistyped
+    /// character corrupts the whole string instead of silently resolving to
+    /// a different glyph, and bech32m-encodes the result behind the
+    /// `glyph1…` human-readable prefix.
+    fn encode_glyph_ref(&self, block_height: u32, tx_index: u32, holder_address: Option<&str>) -> Result<String, GlyphError> {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&block_height.to_be_bytes());
+        payload.extend_from_slice(&tx_index.to_be_bytes());
+        if let Some(address) = holder_address {
+            payload.extend_from_slice(address.as_bytes());
+        }
+
+        let jumbled = self.f4jumble(&payload, true);
+        bech32::encode(GLYPH_REF_HRP, jumbled.to_base32(), Variant::Bech32m)
+            .map_err(|e| GlyphError::InvalidSymbol(format!("Failed to encode glyph reference: {}", e)))
+    }
+
+    /// Reverses `encode_glyph_ref`: validates the bech32m checksum, undoes
+    /// the jumble, and splits the payload back into its block height, tx
+    /// index, and optional holder address.
+    fn decode_glyph_ref(&self, encoded: &str) -> Result<(u32, u32, Option<String>), GlyphError> {
+        let (hrp, data, variant) = bech32::decode(encoded)
+            .map_err(|e| GlyphError::InvalidSymbol(format!("Invalid glyph reference checksum: {}", e)))?;
+
+        if hrp != GLYPH_REF_HRP || variant != Variant::Bech32m {
+            return Err(GlyphError::InvalidSymbol(format!("Not a glyph reference: {}", encoded)));
+        }
+
+        let jumbled = Vec::<u8>::from_base32(&data)
+            .map_err(|e| GlyphError::InvalidSymbol(format!("Invalid glyph reference payload: {}", e)))?;
+        if jumbled.len() < 8 {
+            return Err(GlyphError::InvalidSymbol("Glyph reference payload too short".to_string()));
+        }
+
+        let payload = self.f4jumble(&jumbled, false);
+
+        let block_height = u32::from_be_bytes(payload[0..4].try_into().unwrap());
+        let tx_index = u32::from_be_bytes(payload[4..8].try_into().unwrap());
+        let holder_address = if payload.len() > 8 {
+            Some(String::from_utf8(payload[8..].to_vec())
+                .map_err(|e| GlyphError::InvalidSymbol(format!("Invalid holder address bytes in glyph reference: {}", e)))?)
+        } else {
+            None
+        };
+
+        Ok((block_height, tx_index, holder_address))
+    }
+
     fn select_utxo(&self, amount_needed_btc: f64) -> Result<PsbtInput, GlyphError> {
-        let unspent = self.rpc_client.list_unspent(None, None, None, None, None)?;
+        let unspent = self.signer.list_unspent()?;
         for utxo in unspent {
             if utxo.amount.to_btc() >= amount_needed_btc {
                 return Ok(utxo);
@@ -128,31 +758,243 @@ impl GlyphProtocol {
         }
     }
 
-    fn create_htlc_script(&self, receiver_pubkey: &PublicKey, sender_pubkey: &PublicKey, 
-                          secret_hash: &[u8], timelock: u32) -> Script {
+    /// MuSig-style aggregate of both HTLC parties' keys, used as the
+    /// taproot internal key so a cooperative close can spend via the key
+    /// path alone — cheaper, and indistinguishable on-chain from any other
+    /// P2TR output. This is just a point sum, not a full MuSig2 key
+    /// aggregation with per-key coefficients, so it doesn't defend against
+    /// a rogue-key attack between untrusted parties; that's fine here since
+    /// both keys come from each side's own `initiate_swap`/
+    /// `participate_in_swap` call, not an arbitrary third party.
+    fn musig_aggregate_pubkey(&self, a: &PublicKey, b: &PublicKey) -> Result<PublicKey, GlyphError> {
+        let combined = secp256k1::PublicKey::combine_keys(&[&a.key, &b.key])
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to aggregate HTLC keys: {}", e)))?;
+        Ok(PublicKey { compressed: true, key: combined })
+    }
+
+    /// Hashlock leaf: `OP_HASH160 <secret_hash> OP_EQUALVERIFY <receiver_xonly> OP_CHECKSIG`.
+    /// The receiver can spend as soon as they reveal `preimage` such that
+    /// `HASH160(preimage) == secret_hash`.
+    fn htlc_hashlock_leaf_script(&self, receiver_pubkey: &PublicKey, secret_hash: &[u8]) -> Script {
+        let x_only = &receiver_pubkey.key.serialize()[1..33];
         Script::new()
-            .push_opcode(OP_DUP)
             .push_opcode(OP_HASH160)
             .push_slice(secret_hash)
             .push_opcode(OP_EQUALVERIFY)
+            .push_slice(x_only)
             .push_opcode(OP_CHECKSIG)
-            .push_opcode(OP_IF)
-            .push_key(receiver_pubkey)
-            .push_opcode(OP_ELSE)
+    }
+
+    /// Timelock (refund) leaf: `<timelock> OP_CLTV OP_DROP <sender_xonly> OP_CHECKSIG`.
+    /// The sender can only reclaim the output once `nLockTime >= timelock`.
+    fn htlc_timelock_leaf_script(&self, sender_pubkey: &PublicKey, timelock: u32) -> Script {
+        let x_only = &sender_pubkey.key.serialize()[1..33];
+        Script::new()
             .push_int(timelock as i64)
             .push_opcode(OP_CHECKLOCKTIMEVERIFY)
             .push_opcode(OP_DROP)
-            .push_key(sender_pubkey)
-            .push_opcode(OP_ENDIF)
+            .push_slice(x_only)
             .push_opcode(OP_CHECKSIG)
     }
 
+    /// `tagged_hash("TapBranch", ...)` over two sibling nodes (leaf hashes
+    /// or branch hashes), ordered per BIP-341 so both parties compute the
+    /// same root regardless of which leaf they built first.
+    fn tap_branch_hash(&self, a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        let mut preimage = left.to_vec();
+        preimage.extend_from_slice(&right);
+        self.tagged_hash("TapBranch", &preimage)
+    }
+
+    /// Control block to spend one of the HTLC's two leaves: leaf version +
+    /// output-key parity, the aggregate internal x-only key, then the
+    /// sibling leaf's hash as the tree's single merkle-path step.
+    fn htlc_leaf_control_block(&self, internal_pubkey: &PublicKey, merkle_root: [u8; 32], sibling_leaf_hash: [u8; 32]) -> Result<Vec<u8>, GlyphError> {
+        let (_, output_is_odd) = self.tweak_taproot_key(internal_pubkey, Some(merkle_root))?;
+        let mut control_block = vec![TAPROOT_LEAF_VERSION | (output_is_odd as u8)];
+        control_block.extend_from_slice(&internal_pubkey.key.serialize()[1..33]);
+        control_block.extend_from_slice(&sibling_leaf_hash);
+        Ok(control_block)
+    }
+
+    /// The hashlock leaf script plus the control block needed to spend it.
+    fn htlc_hashlock_spend_info(&self, receiver_pubkey: &PublicKey, sender_pubkey: &PublicKey,
+                               secret_hash: &[u8], timelock: u32) -> Result<(Script, Vec<u8>), GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(receiver_pubkey, sender_pubkey)?;
+        let hashlock_leaf = self.htlc_hashlock_leaf_script(receiver_pubkey, secret_hash);
+        let timelock_leaf = self.htlc_timelock_leaf_script(sender_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&hashlock_leaf), self.tap_leaf_hash(&timelock_leaf));
+        let control_block = self.htlc_leaf_control_block(&internal_pubkey, merkle_root, self.tap_leaf_hash(&timelock_leaf))?;
+        Ok((hashlock_leaf, control_block))
+    }
+
+    /// The timelock leaf script plus the control block needed to spend it.
+    fn htlc_timelock_spend_info(&self, receiver_pubkey: &PublicKey, sender_pubkey: &PublicKey,
+                               secret_hash: &[u8], timelock: u32) -> Result<(Script, Vec<u8>), GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(receiver_pubkey, sender_pubkey)?;
+        let hashlock_leaf = self.htlc_hashlock_leaf_script(receiver_pubkey, secret_hash);
+        let timelock_leaf = self.htlc_timelock_leaf_script(sender_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&hashlock_leaf), self.tap_leaf_hash(&timelock_leaf));
+        let control_block = self.htlc_leaf_control_block(&internal_pubkey, merkle_root, self.tap_leaf_hash(&hashlock_leaf))?;
+        Ok((timelock_leaf, control_block))
+    }
+
+    /// Builds the taproot output funding an HTLC. The internal key is the
+    /// `musig_aggregate_pubkey` of both parties, so a cooperative close can
+    /// spend via the key path; the script tree commits to both the
+    /// hashlock and timelock leaves for a unilateral close either side can
+    /// fall back to. Shares the same `tap_leaf_hash`/`tweak_taproot_key`
+    /// machinery `create_taproot_address`'s Nostr integration uses.
+    fn create_htlc_script(&self, receiver_pubkey: &PublicKey, sender_pubkey: &PublicKey,
+                          secret_hash: &[u8], timelock: u32) -> Result<Script, GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(receiver_pubkey, sender_pubkey)?;
+        let hashlock_leaf = self.htlc_hashlock_leaf_script(receiver_pubkey, secret_hash);
+        let timelock_leaf = self.htlc_timelock_leaf_script(sender_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&hashlock_leaf), self.tap_leaf_hash(&timelock_leaf));
+        let (tweaked_xonly, _) = self.tweak_taproot_key(&internal_pubkey, Some(merkle_root))?;
+        Ok(Address::p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(tweaked_xonly), self.network).script_pubkey())
+    }
+
+    fn random_preimage(&self) -> [u8; 32] {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let counter = COUNTER.fetch_add(1, Ordering::SeqCst);
+
+        let mut seed = Vec::new();
+        seed.extend_from_slice(&nanos.to_le_bytes());
+        seed.extend_from_slice(&counter.to_le_bytes());
+        seed.extend_from_slice(&std::process::id().to_le_bytes());
+        sha256::Hash::hash(&seed).into_inner()
+    }
+
+    fn persist_swap(&self, record: &SwapRecord) -> Result<(), GlyphError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.swap_store_path)
+            .map_err(|e| GlyphError::NetworkError(format!("Failed to open swap store: {}", e)))?;
+        file.write_all(Self::format_swap_record(record).as_bytes())
+            .map_err(|e| GlyphError::NetworkError(format!("Failed to persist swap record: {}", e)))
+    }
+
+    fn format_swap_record(record: &SwapRecord) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}\n",
+            record.role.as_str(),
+            record.htlc_txid,
+            record.vout,
+            record.amount,
+            hex::encode(&record.secret_hash),
+            record.preimage.as_ref().map(hex::encode).unwrap_or_default(),
+            record.timelock,
+            record.counterparty_pubkey,
+            record.own_pubkey,
+            record.destination_address,
+            record.peer_htlc_txid.as_deref().unwrap_or_default(),
+            record.peer_vout.map(|v| v.to_string()).unwrap_or_default(),
+            record.peer_timelock.map(|v| v.to_string()).unwrap_or_default(),
+        )
+    }
+
+    fn parse_swap_record(line: &str) -> Result<Option<SwapRecord>, GlyphError> {
+        let fields: Vec<&str> = line.split('|').collect();
+        if fields.len() != 13 {
+            return Ok(None);
+        }
+        let preimage = if fields[5].is_empty() {
+            None
+        } else {
+            Some(hex::decode(fields[5]).map_err(|_| GlyphError::InvalidTransaction("Invalid preimage in swap store".to_string()))?)
+        };
+        let peer_htlc_txid = if fields[10].is_empty() { None } else { Some(fields[10].to_string()) };
+        let peer_vout = if fields[11].is_empty() {
+            None
+        } else {
+            Some(fields[11].parse().map_err(|_| GlyphError::InvalidTransaction("Invalid peer_vout in swap store".to_string()))?)
+        };
+        let peer_timelock = if fields[12].is_empty() {
+            None
+        } else {
+            Some(fields[12].parse().map_err(|_| GlyphError::InvalidTransaction("Invalid peer_timelock in swap store".to_string()))?)
+        };
+        Ok(Some(SwapRecord {
+            role: SwapRole::from_str(fields[0])?,
+            htlc_txid: fields[1].to_string(),
+            vout: fields[2].parse().map_err(|_| GlyphError::InvalidTransaction("Invalid vout in swap store".to_string()))?,
+            amount: fields[3].parse().map_err(|_| GlyphError::InvalidTransaction("Invalid amount in swap store".to_string()))?,
+            secret_hash: hex::decode(fields[4]).map_err(|_| GlyphError::InvalidTransaction("Invalid secret_hash in swap store".to_string()))?,
+            preimage,
+            timelock: fields[6].parse().map_err(|_| GlyphError::InvalidTransaction("Invalid timelock in swap store".to_string()))?,
+            counterparty_pubkey: fields[7].to_string(),
+            own_pubkey: fields[8].to_string(),
+            destination_address: fields[9].to_string(),
+            peer_htlc_txid,
+            peer_vout,
+            peer_timelock,
+        }))
+    }
+
+    fn load_swaps(&self) -> Result<Vec<SwapRecord>, GlyphError> {
+        let mut contents = String::new();
+        match OpenOptions::new().read(true).open(&self.swap_store_path) {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents)
+                    .map_err(|e| GlyphError::NetworkError(format!("Failed to read swap store: {}", e)))?;
+            }
+            Err(_) => return Ok(Vec::new()),
+        }
+
+        let mut records = Vec::new();
+        for line in contents.lines() {
+            if let Some(record) = Self::parse_swap_record(line)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Rewrites the swap store in place, replacing the record matching
+    /// `htlc_txid`:`vout` with whatever `f` leaves behind. Used by the
+    /// watcher to persist a newly-learned peer leg or preimage so restarts
+    /// don't lose what's already been discovered.
+    fn update_swap<F: FnOnce(&mut SwapRecord)>(&self, htlc_txid: &str, vout: u32, f: F) -> Result<(), GlyphError> {
+        let mut records = self.load_swaps()?;
+        let record = records.iter_mut()
+            .find(|r| r.htlc_txid == htlc_txid && r.vout == vout)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No tracked swap for {}:{}", htlc_txid, vout)))?;
+        f(record);
+
+        let mut contents = String::new();
+        for record in &records {
+            contents.push_str(&Self::format_swap_record(record));
+        }
+        std::fs::write(&self.swap_store_path, contents)
+            .map_err(|e| GlyphError::NetworkError(format!("Failed to rewrite swap store: {}", e)))
+    }
+
+    fn find_swap(&self, htlc_txid: &str, vout: u32) -> Result<SwapRecord, GlyphError> {
+        self.load_swaps()?
+            .into_iter()
+            .find(|r| r.htlc_txid == htlc_txid && r.vout == vout)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No tracked swap for {}:{}", htlc_txid, vout)))
+    }
+
+    /// Looks a tracked swap up by the counterparty's linked outpoint rather
+    /// than our own. `claim_swap` needs this: the leg that pays us is never
+    /// the one we persisted as `htlc_txid`:`vout`, it's `peer_htlc_txid`:
+    /// `peer_vout` on the record we made for our own side.
+    fn find_swap_by_peer(&self, peer_htlc_txid: &str, peer_vout: u32) -> Result<SwapRecord, GlyphError> {
+        self.load_swaps()?
+            .into_iter()
+            .find(|r| r.peer_htlc_txid.as_deref() == Some(peer_htlc_txid) && r.peer_vout == Some(peer_vout))
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No tracked swap linked to peer HTLC {}:{}", peer_htlc_txid, peer_vout)))
+    }
+
     fn etch_glyph(&self, name: &str, divisibility: u8, symbol: char, premine: u64,
-                  mint_cap: Option<u64>, mint_amount: Option<u64>, 
+                  mint_cap: Option<u64>, mint_amount: Option<u64>,
                   start_height: Option<u32>, end_height: Option<u32>,
                   start_offset: Option<u32>, end_offset: Option<u32>,
                   destination_address: &str, change_address: Option<&str>,
-                  fee_per_byte: u64, live: bool, nostr_pubkey: Option<&str>) -> Result<String, GlyphError> {
+                  fee_per_byte: u64, live: bool, nostr_pubkey: Option<&str>, psbt: bool,
+                  multisig: Option<&MultisigConfig>, multisig_taproot: bool) -> Result<String, GlyphError> {
         let name_int = self.symbol_to_int(name)?;
         let mut glyphstone_data = vec![b'E'];
         glyphstone_data.extend_from_slice(&self.encode_varint(name_int));
@@ -162,21 +1004,25 @@ impl GlyphProtocol {
                                                         start_height, end_height, start_offset, end_offset);
 
         let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
-        
+
         let destination_output = if premine > 0 {
-            Some(self.create_glyph_output(premine, divisibility, destination_address, nostr_pubkey)?)
+            Some(match multisig {
+                Some(config) => self.create_glyph_multisig_output(premine, divisibility, config, multisig_taproot)?,
+                None => self.create_glyph_output(premine, divisibility, destination_address, nostr_pubkey)?,
+            })
         } else {
             None
         };
 
-        self.construct_and_broadcast_transaction(glyphstone_output, destination_output, change_address, fee_per_byte, live)
+        self.construct_and_broadcast_transaction(glyphstone_output, destination_output, change_address, fee_per_byte, live, psbt)
     }
 
     fn mint_glyph(&self, glyph_id: &str, amount: u64, destination_address: &str,
                   change_address: Option<&str>, fee_per_byte: u64, live: bool,
-                  nostr_pubkey: Option<&str>) -> Result<String, GlyphError> {
+                  nostr_pubkey: Option<&str>, psbt: bool,
+                  multisig: Option<&MultisigConfig>, multisig_taproot: bool) -> Result<String, GlyphError> {
         let glyph_info = self.get_glyph_info(glyph_id)?;
-        let current_height = self.rpc_client.get_block_count()? as u32;
+        let current_height = self.backend.get_block_count()?;
 
         if !self.is_mint_open(&glyph_info, current_height) {
             return Err(GlyphError::InvalidTransaction(format!("Mint is closed for Glyph {}", glyph_id)));
@@ -188,35 +1034,98 @@ impl GlyphProtocol {
             }
         }
 
-        let (block_height, tx_index) = Self::parse_glyph_id(glyph_id)?;
+        let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
         let mut glyphstone_data = vec![b'M'];
         glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
         glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
         glyphstone_data.extend_from_slice(&self.encode_varint(amount));
 
         let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
-        
-        let destination_output = self.create_glyph_output(amount, *glyph_info.get("divisibility").unwrap() as u8, destination_address, nostr_pubkey)?;
+        let divisibility = *glyph_info.get("divisibility").unwrap() as u8;
+
+        let destination_output = match multisig {
+            Some(config) => self.create_glyph_multisig_output(amount, divisibility, config, multisig_taproot)?,
+            None => self.create_glyph_output(amount, divisibility, destination_address, nostr_pubkey)?,
+        };
 
-        self.construct_and_broadcast_transaction(glyphstone_output, Some(destination_output), change_address, fee_per_byte, live)
+        self.construct_and_broadcast_transaction(glyphstone_output, Some(destination_output), change_address, fee_per_byte, live, psbt)
     }
 
-    fn transfer_glyph(&self, glyph_id: &str, input_txid: &str, input_vout: u32, amount: u64,
-                      destination_address: &str, change_address: Option<&str>,
-                      fee_per_byte: u64, live: bool, nostr_pubkey: Option<&str>) -> Result<String, GlyphError> {
-        let (block_height, tx_index) = Self::parse_glyph_id(glyph_id)?;
-        
-        let input_glyphs = self.get_glyph_balance(input_txid, input_vout, glyph_id)?;
-        if input_glyphs < amount {
-            return Err(GlyphError::InsufficientFunds(format!("Insufficient Glyphs in input. Available: {}, Requested: {}", input_glyphs, amount)));
+    /// Encodes a set of edicts as a delta-compressed sequence: sorted by
+    /// glyph id, each entry stores the block-height/tx-index delta from the
+    /// previous edict, then the amount and output index as varints. Keeps the
+    /// OP_RETURN small even when a transfer fans out across many recipients.
+    fn encode_edicts(&self, edicts: &[Edict]) -> Vec<u8> {
+        let mut sorted = edicts.to_vec();
+        sorted.sort_by_key(|e| e.glyph_id);
+
+        let mut data = Vec::new();
+        let mut prev = (0u32, 0u32);
+        for edict in &sorted {
+            let block_delta = edict.glyph_id.0 - prev.0;
+            data.extend_from_slice(&self.encode_varint(block_delta as u64));
+            if block_delta == 0 {
+                data.extend_from_slice(&self.encode_varint((edict.glyph_id.1 - prev.1) as u64));
+            } else {
+                data.extend_from_slice(&self.encode_varint(edict.glyph_id.1 as u64));
+            }
+            data.extend_from_slice(&self.encode_varint(edict.amount));
+            data.extend_from_slice(&self.encode_varint(edict.output_index as u64));
+            prev = edict.glyph_id;
         }
+        data
+    }
 
-        let mut glyphstone_data = vec![b'T'];
-        glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
-        glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
-        glyphstone_data.extend_from_slice(&self.encode_varint(amount));
-        glyphstone_data.extend_from_slice(&self.encode_varint(1)); // Output index 1 for the destination output
+    fn decode_edicts(&self, data: &[u8]) -> Result<Vec<Edict>, GlyphError> {
+        let mut edicts = Vec::new();
+        let mut prev = (0u32, 0u32);
+        let mut rest = data;
+
+        while !rest.is_empty() {
+            let (block_delta, r) = self.decode_varint(rest)?;
+            rest = r;
+            let (tx_index, r) = self.decode_varint(rest)?;
+            rest = r;
+            let (amount, r) = self.decode_varint(rest)?;
+            rest = r;
+            let (output_index, r) = self.decode_varint(rest)?;
+            rest = r;
+
+            let glyph_id = if block_delta == 0 {
+                (prev.0, prev.1 + tx_index as u32)
+            } else {
+                (prev.0 + block_delta as u32, tx_index as u32)
+            };
+            prev = glyph_id;
+
+            edicts.push(Edict { glyph_id, amount, output_index: output_index as u32 });
+        }
+
+        Ok(edicts)
+    }
+
+    /// Moves glyphs out of `input_txid:input_vout` according to `edicts`,
+    /// sending `destination_addresses[i]` to output index `i + 1`. Any edict
+    /// pointing at a non-existent output, or whose amount exceeds the glyph's
+    /// available balance on the input, turns the whole glyphstone into a
+    /// cenotaph and burns the glyphs rather than silently succeeding.
+    fn transfer_glyph(&self, input_txid: &str, input_vout: u32, edicts: &[Edict],
+                      destination_addresses: &[String], change_address: Option<&str>,
+                      fee_per_byte: u64, live: bool, nostr_pubkey: Option<&str>, psbt: bool) -> Result<String, GlyphError> {
+        let mut available: HashMap<(u32, u32), u64> = HashMap::new();
+        for edict in edicts {
+            if !available.contains_key(&edict.glyph_id) {
+                let glyph_id_str = format!("{}:{}", edict.glyph_id.0, edict.glyph_id.1);
+                let balance = self.get_glyph_balance(input_txid, input_vout, &glyph_id_str)?;
+                available.insert(edict.glyph_id, balance);
+            }
+        }
 
+        let glyphstone_data = {
+            let mut data = vec![b'T'];
+            data.extend_from_slice(&self.encode_edicts(edicts));
+            data
+        };
         let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
 
         let txin = TxIn {
@@ -226,13 +1135,23 @@ impl GlyphProtocol {
             witness: vec![],
         };
 
-        let destination_output = if destination_address.starts_with("OP_RETURN") {
-            TxOut { value: 0, script_pubkey: Script::new_op_return(&[]) }
-        } else {
-            self.create_glyph_output(amount, 0, destination_address, nostr_pubkey)?
-        };
+        let mut destination_outputs = Vec::new();
+        for (i, address) in destination_addresses.iter().enumerate() {
+            let output_index = (i + 1) as u32;
+            let output_amount: u64 = edicts.iter()
+                .filter(|e| e.output_index == output_index)
+                .map(|e| e.amount)
+                .sum();
+
+            let output = if address.starts_with("OP_RETURN") {
+                TxOut { value: 0, script_pubkey: Script::new_op_return(&[]) }
+            } else {
+                self.create_glyph_output(output_amount, 0, address, nostr_pubkey)?
+            };
+            destination_outputs.push(output);
+        }
 
-        self.construct_and_broadcast_transaction(glyphstone_output, Some(destination_output), change_address, fee_per_byte, live)
+        self.construct_and_broadcast_edict_transaction(glyphstone_output, destination_outputs, edicts, &available, change_address, fee_per_byte, live, psbt)
     }
 
     fn is_valid_glyph_name(&self, name: &str) -> bool {
@@ -282,15 +1201,14 @@ impl GlyphProtocol {
     }
 
     fn get_glyph_info(&self, glyph_id: &str) -> Result<HashMap<String, u64>, GlyphError> {
-        let (block_height, tx_index) = Self::parse_glyph_id(glyph_id)?;
-        let block_hash = self.rpc_client.get_block_hash(block_height as u64)?;
-        let block = self.rpc_client.get_block(&block_hash)?;
-    
-        if tx_index >= block.txdata.len() as u32 {
+        let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
+        let block_txs = self.backend.get_block_transactions(block_height)?;
+
+        if tx_index >= block_txs.len() as u32 {
             return Err(GlyphError::InvalidTransaction(format!("Transaction index {} out of range for block {}", tx_index, block_height)));
         }
-    
-        let transaction = &block.txdata[tx_index as usize];
+
+        let transaction = &block_txs[tx_index as usize];
     
         let glyphstone_data = transaction.output.iter()
             .filter_map(|output| {
@@ -317,7 +1235,7 @@ impl GlyphProtocol {
         glyph_info.insert("etch_height".to_string(), etch_height as u64);
         glyph_info.insert("minted_count".to_string(), 0);
     
-        if glyphstone_data[0] != b'E' {
+        if glyphstone_data.is_empty() || glyphstone_data[0] != b'E' {
             return Err(GlyphError::InvalidTransaction("Invalid Glyphstone data: doesn't start with 'E'".to_string()));
         }
     
@@ -375,54 +1293,96 @@ impl GlyphProtocol {
     }
     
     fn get_glyph_balance(&self, txid: &str, vout: u32, glyph_id: &str) -> Result<u64, GlyphError> {
-        let raw_tx = self.rpc_client.get_raw_transaction_verbose(&Txid::from_str(txid).map_err(|_| GlyphError::InvalidTransaction("Invalid txid".to_string()))?)?;
-        
-        if vout as usize >= raw_tx.vout.len() {
-            return Err(GlyphError::InvalidTransaction(format!("Output index {} out of range for transaction {}", vout, txid)));
-        }
-    
-        let output = &raw_tx.vout[vout as usize];
-    
+        let txid_parsed = Txid::from_str(txid).map_err(|_| GlyphError::InvalidTransaction("Invalid txid".to_string()))?;
+        let tx = self.backend.get_transaction(&txid_parsed)?;
+
+        let output = tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("Output index {} out of range for transaction {}", vout, txid)))?;
+
         // Check if the output is unspent
-        if self.rpc_client.get_tx_out(&Txid::from_str(txid).unwrap(), vout, Some(true)).is_none() {
+        if self.backend.get_tx_out(&txid_parsed, vout)?.is_none() {
             return Err(GlyphError::InvalidTransaction(format!("UTXO {}:{} has been spent", txid, vout)));
         }
-    
-        let script_asm = &output.script_pub_key.asm;
+
+        if self.is_cenotaph(output) {
+            return Ok(0);
+        }
+
+        let script_asm = output.script_pubkey.asm();
         let parts: Vec<&str> = script_asm.split_whitespace().collect();
-        
+
         if parts.len() < 3 || parts[1] != "OP_13" {
             return Err(GlyphError::InvalidTransaction(format!("UTXO {}:{} does not contain valid Glyphstone data", txid, vout)));
         }
-    
+
         let glyphstone_data = hex::decode(parts[2]).map_err(|_| GlyphError::InvalidTransaction("Invalid Glyphstone data".to_string()))?;
-        self.decode_glyph_balance(&glyphstone_data, glyph_id)
+        self.decode_glyph_balance(&glyphstone_data, glyph_id, vout, &tx)
     }
-    
-    fn decode_glyph_balance(&self, glyphstone_data: &[u8], glyph_id: &str) -> Result<u64, GlyphError> {
-        if glyphstone_data[0] != b'T' {
+
+    /// Walks every edict in the glyphstone and sums only the ones that both
+    /// match `glyph_id` and land on `vout` — a single `T` stone can carry
+    /// edicts for several glyphs across several outputs. Mirrors the
+    /// cenotaph rule `construct_and_broadcast_edict_transaction` enforces
+    /// when *we* build a transfer: an edict pointing past the end of `tx`'s
+    /// outputs invalidates the whole stone, and a cumulative amount that
+    /// overspends what `tx`'s own spent input(s) actually carried for that
+    /// glyph does too — otherwise a forged glyphstone could declare a
+    /// balance no legitimate transfer ever produced. The overspend check is
+    /// skipped for a glyph whose input isn't itself a decodable `T`
+    /// transfer (e.g. a premine `E` issuance), since this function has no
+    /// way to learn that input's true balance.
+    fn decode_glyph_balance(&self, glyphstone_data: &[u8], glyph_id: &str, vout: u32, tx: &Transaction) -> Result<u64, GlyphError> {
+        if glyphstone_data.is_empty() || glyphstone_data[0] != b'T' {
             return Err(GlyphError::InvalidTransaction("Invalid Glyphstone data: doesn't start with 'T'".to_string()));
         }
-    
-        let mut data = &glyphstone_data[1..];
-        let (block_height, rest) = self.decode_varint(data)?;
-        let (tx_index, rest) = self.decode_varint(rest)?;
-    
-        if format!("{}:{}", block_height, tx_index) != glyph_id {
-            return Err(GlyphError::InvalidTransaction(format!("Glyphstone does not contain Glyph with ID {}", glyph_id)));
+
+        let (target_block, target_tx) = self.parse_glyph_id(glyph_id)?;
+        let edicts = self.decode_edicts(&glyphstone_data[1..])?;
+
+        if edicts.iter().any(|e| e.output_index as usize >= tx.output.len()) {
+            return Ok(0);
         }
-    
-        let (balance, _) = self.decode_varint(rest)?;
+
+        let mut spent: HashMap<(u32, u32), u64> = HashMap::new();
+        let mut available_cache: HashMap<(u32, u32), Option<u64>> = HashMap::new();
+        for edict in &edicts {
+            let spent_so_far = spent.entry(edict.glyph_id).or_insert(0);
+            *spent_so_far += edict.amount;
+
+            let available = available_cache.entry(edict.glyph_id).or_insert_with(|| {
+                let glyph_id_str = format!("{}:{}", edict.glyph_id.0, edict.glyph_id.1);
+                let mut total = 0u64;
+                for txin in &tx.input {
+                    match self.get_glyph_balance(&txin.previous_output.txid.to_string(), txin.previous_output.vout, &glyph_id_str) {
+                        Ok(balance) => total += balance,
+                        Err(_) => return None,
+                    }
+                }
+                Some(total)
+            });
+
+            if let Some(available) = available {
+                if *spent_so_far > *available {
+                    return Ok(0);
+                }
+            }
+        }
+
+        let balance = edicts.iter()
+            .filter(|e| e.glyph_id == (target_block, target_tx) && e.output_index == vout)
+            .map(|e| e.amount)
+            .sum();
+
         Ok(balance)
     }
     
     fn construct_and_broadcast_transaction(&self, glyphstone_output: TxOut,
                                            destination_output: Option<TxOut>,
                                            change_address: Option<&str>,
-                                           fee_per_byte: u64, live: bool) -> Result<String, GlyphError> {
+                                           fee_per_byte: u64, live: bool, psbt: bool) -> Result<String, GlyphError> {
         let amount_needed_btc = 0.0001; // Initial estimate
         let utxo = self.select_utxo(amount_needed_btc)?;
-    
+
         let mut tx = Transaction {
             version: 2,
             lock_time: 0,
@@ -434,14 +1394,14 @@ impl GlyphProtocol {
             }],
             output: vec![glyphstone_output],
         };
-    
+
         if let Some(dest_output) = destination_output {
             tx.output.push(dest_output);
         }
-    
+
         let tx_size = tx.get_weight() as u64;
         let fee = tx_size * fee_per_byte;
-    
+
         if let Some(change_addr) = change_address {
             let change = utxo.amount.to_sat() - fee - tx.output.iter().map(|o| o.value).sum::<u64>();
             if change > 0 {
@@ -452,7 +1412,7 @@ impl GlyphProtocol {
                 });
             }
         }
-    
+
         if self.is_cenotaph(&glyphstone_output) {
             println!("Warning: Malformed glyphstone detected. Treating as cenotaph.");
             tx.output = vec![TxOut {
@@ -460,37 +1420,386 @@ impl GlyphProtocol {
                 script_pubkey: Script::new_op_return(&[]),
             }];
         }
-    
+
+        if psbt {
+            return self.build_psbt(&tx, vec![utxo]);
+        }
+
         if live {
-            let signed_tx = self.rpc_client.sign_raw_transaction_with_wallet(&tx, None, None)?;
-            let txid = self.rpc_client.send_raw_transaction(&signed_tx.hex)?;
+            let signed_tx = self.signer.sign_transaction(&tx)?;
+            let txid = self.backend.broadcast(&signed_tx)?;
             Ok(txid.to_string())
         } else {
             println!("{:#?}", tx);
             Ok(tx.txid().to_string())
         }
     }
-    
+
     fn is_cenotaph(&self, glyphstone_output: &TxOut) -> bool {
         let script = &glyphstone_output.script_pubkey;
         script.len() < 2 || script[0] != OP_RETURN.into_u8() || script[1] != OP_13.into_u8()
     }
-    
-    fn create_taproot_address(&self, bitcoin_address: &str, nostr_pubkey: Option<&str>) -> Result<Address, GlyphError> {
-        let addr = Address::from_str(bitcoin_address)?;
-        let script_pubkey = addr.script_pubkey();
-        
-        let nostr_leaf = if let Some(pubkey) = nostr_pubkey {
-            Script::new_v1_p2tr(&Secp256k1::new(), &PublicKey::from_str(pubkey)?, None)
+
+    /// Serializes an assembled-but-unsigned transaction as a base64 PSBT so
+    /// an air-gapped signer can sign it offline: `witness_utxo` so the
+    /// signer can compute sighashes without the prevout transactions,
+    /// `non_witness_utxo` (the full prevout tx) for signers that verify
+    /// against it anyway, and a `bip32_derivation` entry for whichever
+    /// pubkey `pubkey_for_address` resolves for the UTXO's own address —
+    /// the same annotation `LedgerSigner::build_device_psbt` adds, just
+    /// sourced from the chain backend instead of a known device path.
+    /// `utxos` is one-to-one with `tx.input`.
+    fn build_psbt(&self, tx: &Transaction, utxos: Vec<bitcoincore_rpc::json::ListUnspentResultEntry>) -> Result<String, GlyphError> {
+        let mut psbt = bitcoin::util::psbt::PartiallySignedTransaction::from_unsigned_tx(tx.clone())
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to build PSBT: {}", e)))?;
+
+        for (input, utxo) in psbt.inputs.iter_mut().zip(&utxos) {
+            input.witness_utxo = Some(TxOut { value: utxo.amount.to_sat(), script_pubkey: utxo.script_pub_key.clone() });
+
+            if let Ok(prevout_tx) = self.backend.get_transaction(&utxo.txid) {
+                input.non_witness_utxo = Some(prevout_tx);
+            }
+
+            if let Some(address) = &utxo.address {
+                if let Ok(pubkey) = self.backend.pubkey_for_address(&address.to_string()) {
+                    input.bip32_derivation.insert(pubkey.key, (Fingerprint::default(), DerivationPath::from(vec![])));
+                }
+            }
+        }
+
+        Ok(psbt.to_string())
+    }
+
+    /// Accepts a PSBT that's already been signed by an external/offline
+    /// signer, finalizes it, and broadcasts the resulting transaction —
+    /// the other half of the cold-signing round trip started by `build_psbt`.
+    fn finalize_and_broadcast(&self, psbt_base64: &str) -> Result<String, GlyphError> {
+        let final_tx_hex = self.signer.finalize_psbt(psbt_base64)?;
+        let txid = self.backend.broadcast(&final_tx_hex)?;
+        Ok(txid.to_string())
+    }
+
+    /// Combines one partial-signature PSBT per signer once `threshold` of
+    /// them have signed, then finalizes and broadcasts the result. The
+    /// partial signing itself happens outside this process (each signer
+    /// runs their own `signrawtransactionwithkey`/hardware wallet against
+    /// the PSBT from `build_psbt`); this just aggregates what comes back.
+    fn combine_and_finalize_multisig(&self, partial_psbts: &[String]) -> Result<String, GlyphError> {
+        let combined = self.signer.combine_psbt(partial_psbts)?;
+        self.finalize_and_broadcast(&combined)
+    }
+
+    /// Like `construct_and_broadcast_transaction`, but for `T` glyphstones
+    /// carrying a list of edicts landing on several outputs. Any edict that
+    /// points past the end of the output list, or whose cumulative amount for
+    /// a glyph exceeds what's `available` on the spent input, burns the whole
+    /// glyphstone as a cenotaph instead of letting the transfer through.
+    fn construct_and_broadcast_edict_transaction(&self, glyphstone_output: TxOut,
+                                                 destination_outputs: Vec<TxOut>,
+                                                 edicts: &[Edict], available: &HashMap<(u32, u32), u64>,
+                                                 change_address: Option<&str>,
+                                                 fee_per_byte: u64, live: bool, psbt: bool) -> Result<String, GlyphError> {
+        let amount_needed_btc = 0.0001; // Initial estimate
+        let utxo = self.select_utxo(amount_needed_btc)?;
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(utxo.txid, utxo.vout),
+                script_sig: Script::new(),
+                sequence: 0xFFFFFFFF,
+                witness: vec![],
+            }],
+            output: vec![glyphstone_output.clone()],
+        };
+        tx.output.extend(destination_outputs);
+
+        let tx_size = tx.get_weight() as u64;
+        let fee = tx_size * fee_per_byte;
+
+        if let Some(change_addr) = change_address {
+            let change = utxo.amount.to_sat() - fee - tx.output.iter().map(|o| o.value).sum::<u64>();
+            if change > 0 {
+                let change_script = Address::from_str(change_addr)?.script_pubkey();
+                tx.output.push(TxOut {
+                    value: change,
+                    script_pubkey: change_script,
+                });
+            }
+        }
+
+        let mut cenotaph = self.is_cenotaph(&glyphstone_output);
+        if !cenotaph {
+            let mut spent: HashMap<(u32, u32), u64> = HashMap::new();
+            for edict in edicts {
+                if edict.output_index as usize >= tx.output.len() {
+                    cenotaph = true;
+                    break;
+                }
+                let spent_so_far = spent.entry(edict.glyph_id).or_insert(0);
+                *spent_so_far += edict.amount;
+                if *spent_so_far > available.get(&edict.glyph_id).copied().unwrap_or(0) {
+                    cenotaph = true;
+                    break;
+                }
+            }
+        }
+
+        if cenotaph {
+            println!("Warning: Malformed glyphstone or edict overrun detected. Treating as cenotaph.");
+            tx.output = vec![TxOut {
+                value: 0,
+                script_pubkey: Script::new_op_return(&[]),
+            }];
+        }
+
+        if psbt {
+            return self.build_psbt(&tx, vec![utxo]);
+        }
+
+        if live {
+            let signed_tx = self.signer.sign_transaction(&tx)?;
+            let txid = self.backend.broadcast(&signed_tx)?;
+            Ok(txid.to_string())
+        } else {
+            println!("{:#?}", tx);
+            Ok(tx.txid().to_string())
+        }
+    }
+
+    fn tagged_hash(&self, tag: &str, msg: &[u8]) -> [u8; 32] {
+        let tag_hash = sha256::Hash::hash(tag.as_bytes());
+        let mut engine = sha256::Hash::engine();
+        engine.input(tag_hash.as_inner());
+        engine.input(tag_hash.as_inner());
+        engine.input(msg);
+        sha256::Hash::from_engine(engine).into_inner()
+    }
+
+    /// `(a + b) mod SECP256K1_ORDER`, for 256-bit big-endian scalars already
+    /// reduced mod `n`. Adds into a 33-byte buffer so the carry out of the
+    /// top byte has somewhere to go, then subtracts `n` at most once —
+    /// `a + b < 2n` whenever `a, b < n`, so one subtraction always suffices.
+    fn scalar_add_mod_n(&self, a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let mut wide = [0u8; 33];
+        let mut carry: u16 = 0;
+        for i in (0..32).rev() {
+            let sum = a[i] as u16 + b[i] as u16 + carry;
+            wide[i + 1] = (sum & 0xFF) as u8;
+            carry = sum >> 8;
+        }
+        wide[0] = carry as u8;
+
+        let mut n_wide = [0u8; 33];
+        n_wide[1..].copy_from_slice(&SECP256K1_ORDER);
+
+        let ge_n = wide.iter().zip(n_wide.iter()).find(|(x, y)| x != y).map_or(true, |(x, y)| x > y);
+        let reduced = if ge_n {
+            let mut result = [0u8; 33];
+            let mut borrow: i16 = 0;
+            for i in (0..33).rev() {
+                let mut diff = wide[i] as i16 - n_wide[i] as i16 - borrow;
+                if diff < 0 { diff += 256; borrow = 1; } else { borrow = 0; }
+                result[i] = diff as u8;
+            }
+            result
         } else {
-            Script::new()
+            wide
         };
-    
-        let taproot_script = Script::new_v1_p2tr(&Secp256k1::new(), &PublicKey::from_slice(&script_pubkey[1..])?, Some(nostr_leaf));
-        
-        Ok(Address::p2tr(&Secp256k1::new(), taproot_script.to_inner()[1..33].try_into().unwrap(), None, self.network))
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&reduced[1..]);
+        out
     }
-    
+
+    /// `(a * b) mod SECP256K1_ORDER` via double-and-add: walk `a`'s bits
+    /// most-significant first, doubling the accumulator and conditionally
+    /// adding `b`, reducing mod `n` at every step via `scalar_add_mod_n`.
+    /// Avoids a 512-bit schoolbook multiply plus division entirely — the
+    /// only primitive it needs is modular addition, which is easy to get
+    /// right by hand.
+    fn scalar_mul_mod_n(&self, a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let mut acc = [0u8; 32];
+        for byte in a {
+            for bit in (0..8).rev() {
+                acc = self.scalar_add_mod_n(&acc, &acc);
+                if (byte >> bit) & 1 == 1 {
+                    acc = self.scalar_add_mod_n(&acc, b);
+                }
+            }
+        }
+        acc
+    }
+
+    /// `(SECP256K1_ORDER - a) mod SECP256K1_ORDER`, i.e. `-a`, for a
+    /// 256-bit scalar already reduced mod `n`.
+    fn scalar_negate_mod_n(&self, a: &[u8; 32]) -> [u8; 32] {
+        if a.iter().all(|&b| b == 0) {
+            return [0u8; 32];
+        }
+        let mut result = [0u8; 32];
+        let mut borrow: i16 = 0;
+        for i in (0..32).rev() {
+            let mut diff = SECP256K1_ORDER[i] as i16 - a[i] as i16 - borrow;
+            if diff < 0 { diff += 256; borrow = 1; } else { borrow = 0; }
+            result[i] = diff as u8;
+        }
+        result
+    }
+
+    /// Bitcoin's own CompactSize, as used inside a TapLeaf hash preimage —
+    /// distinct from this protocol's LEB128 `encode_varint`.
+    fn compact_size(&self, n: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        if n < 0xfd {
+            out.push(n as u8);
+        } else if n <= 0xffff {
+            out.push(0xfd);
+            out.extend_from_slice(&(n as u16).to_le_bytes());
+        } else if n <= 0xffff_ffff {
+            out.push(0xfe);
+            out.extend_from_slice(&(n as u32).to_le_bytes());
+        } else {
+            out.push(0xff);
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+        out
+    }
+
+    /// The single leaf our tap tree holds when a Glyph is integrated with a
+    /// Nostr identity: `<nostr_xonly> OP_CHECKSIG`.
+    fn nostr_leaf_script(&self, nostr_pubkey: &PublicKey) -> Script {
+        let x_only = &nostr_pubkey.key.serialize()[1..33];
+        Script::new()
+            .push_slice(x_only)
+            .push_opcode(OP_CHECKSIG)
+    }
+
+    fn tap_leaf_hash(&self, leaf_script: &Script) -> [u8; 32] {
+        let mut preimage = vec![TAPROOT_LEAF_VERSION];
+        preimage.extend_from_slice(&self.compact_size(leaf_script.len() as u64));
+        preimage.extend_from_slice(leaf_script.as_bytes());
+        self.tagged_hash("TapLeaf", &preimage)
+    }
+
+    /// Tweaks `internal_pubkey` by `t = tagged_hash("TapTweak", internal_xonly || merkle_root)`.
+    /// First flips the internal key's own parity if it's odd (BIP-341 only
+    /// ever works with the even-Y x-only form), then reports whether the
+    /// resulting *output* key came out odd so callers can set the control
+    /// block's parity bit correctly.
+    fn tweak_taproot_key(&self, internal_pubkey: &PublicKey, merkle_root: Option<[u8; 32]>) -> Result<(XOnlyPublicKey, bool), GlyphError> {
+        let secp = Secp256k1::new();
+        let mut internal = internal_pubkey.key;
+        if internal.serialize()[0] == 0x03 {
+            internal = internal.negate(&secp);
+        }
+        let internal_xonly = internal.serialize()[1..33].to_vec();
+
+        let mut preimage = internal_xonly.clone();
+        if let Some(root) = merkle_root {
+            preimage.extend_from_slice(&root);
+        }
+        let tweak = self.tagged_hash("TapTweak", &preimage);
+        let tweak_key = SecretKey::from_slice(&tweak)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid tap tweak: {}", e)))?;
+
+        let tweaked = internal.add_exp_tweak(&secp, &tweak_key)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to tweak taproot key: {}", e)))?;
+
+        let output_is_odd = tweaked.serialize()[0] == 0x03;
+        let tweaked_xonly = XOnlyPublicKey::from_slice(&tweaked.serialize()[1..33])
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid tweaked x-only key: {}", e)))?;
+
+        Ok((tweaked_xonly, output_is_odd))
+    }
+
+    /// Produces the control block needed to spend the Nostr leaf: leaf
+    /// version + output-key parity bit, followed by the internal x-only key.
+    /// With only one leaf in the tree there's no merkle path to append.
+    fn nostr_leaf_control_block(&self, internal_pubkey: &PublicKey, nostr_pubkey: &PublicKey) -> Result<Vec<u8>, GlyphError> {
+        let leaf_script = self.nostr_leaf_script(nostr_pubkey);
+        let merkle_root = self.tap_leaf_hash(&leaf_script);
+        let (_, output_is_odd) = self.tweak_taproot_key(internal_pubkey, Some(merkle_root))?;
+
+        let mut control_block = vec![TAPROOT_LEAF_VERSION | (output_is_odd as u8)];
+        control_block.extend_from_slice(&internal_pubkey.key.serialize()[1..33]);
+        Ok(control_block)
+    }
+
+    /// Builds a key-path + script-path taproot output: the destination's
+    /// x-only key is the internal key, and when `nostr_pubkey` is supplied the
+    /// tap tree gets a single leaf committing to it, so the Nostr identity can
+    /// later spend via the script path while the plain destination key keeps
+    /// key-path spending available.
+    fn create_taproot_address(&self, bitcoin_address: &str, nostr_pubkey: Option<&str>) -> Result<Address, GlyphError> {
+        let internal_pubkey = self.backend.pubkey_for_address(bitcoin_address)?;
+
+        let merkle_root = match nostr_pubkey {
+            Some(pubkey_hex) => {
+                let nostr_pk = PublicKey::from_str(pubkey_hex)
+                    .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid Nostr pubkey: {}", e)))?;
+                Some(self.tap_leaf_hash(&self.nostr_leaf_script(&nostr_pk)))
+            }
+            None => None,
+        };
+
+        let (tweaked_xonly, _output_is_odd) = self.tweak_taproot_key(&internal_pubkey, merkle_root)?;
+        Ok(Address::p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(tweaked_xonly), self.network))
+    }
+
+    /// Classic bare `m-of-n OP_CHECKMULTISIG` witness script for legacy P2WSH
+    /// multisig issuance.
+    fn multisig_witness_script(&self, config: &MultisigConfig) -> Script {
+        let mut script = Script::new()
+            .push_int(config.threshold as i64);
+        for pubkey in &config.signer_pubkeys {
+            script = script.push_slice(&pubkey.key.serialize());
+        }
+        script.push_int(config.signer_pubkeys.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+    }
+
+    /// Tapscript-legal `m-of-n` leaf: `OP_CHECKMULTISIG`/`OP_CHECKMULTISIGVERIFY`
+    /// are disabled opcodes under BIP-342, so a script-path multisig spend
+    /// chains `OP_CHECKSIGADD` instead — first pubkey via `OP_CHECKSIG`,
+    /// each remaining pubkey via `OP_CHECKSIGADD`, then compares the
+    /// accumulated signature count against `threshold`. Pubkeys are x-only,
+    /// matching every other tapscript leaf in this file.
+    fn multisig_tapscript_leaf(&self, config: &MultisigConfig) -> Script {
+        let mut signers = config.signer_pubkeys.iter();
+        let mut script = Script::new()
+            .push_slice(&signers.next().unwrap().key.serialize()[1..33])
+            .push_opcode(OP_CHECKSIG);
+        for pubkey in signers {
+            script = script.push_slice(&pubkey.key.serialize()[1..33])
+                .push_opcode(OP_CHECKSIGADD);
+        }
+        script.push_int(config.threshold as i64)
+            .push_opcode(OP_NUMEQUAL)
+    }
+
+    /// Builds the address a glyph's issuance outputs go to under a multisig
+    /// policy. `taproot = false` wraps the bare multisig script as classic
+    /// P2WSH. `taproot = true` commits a `OP_CHECKSIGADD` tapscript leaf with
+    /// the same threshold/signers as the tap tree's only leaf, with the
+    /// first signer's key as the internal key — key-path spending is the
+    /// first signer's alone, so cooperating signers should always spend via
+    /// the script path to enforce the threshold.
+    fn create_multisig_address(&self, config: &MultisigConfig, taproot: bool) -> Result<Address, GlyphError> {
+        config.validate()?;
+
+        if !taproot {
+            let witness_script = self.multisig_witness_script(config);
+            return Ok(Address::p2wsh(&witness_script, self.network));
+        }
+
+        let leaf_script = self.multisig_tapscript_leaf(config);
+        let merkle_root = self.tap_leaf_hash(&leaf_script);
+        let internal_pubkey = &config.signer_pubkeys[0];
+        let (tweaked_xonly, _output_is_odd) = self.tweak_taproot_key(internal_pubkey, Some(merkle_root))?;
+        Ok(Address::p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(tweaked_xonly), self.network))
+    }
+
     fn add_optional_mint_params(&self, mut glyphstone_data: Vec<u8>, symbol: char, premine: u64,
                                 mint_cap: Option<u64>, mint_amount: Option<u64>, 
                                 start_height: Option<u32>, end_height: Option<u32>,
@@ -549,8 +1858,35 @@ impl GlyphProtocol {
             })
         }
     }
-    
-    fn parse_glyph_id(glyph_id: &str) -> Result<(u32, u32), GlyphError> {
+
+    /// Like `create_glyph_output`, but sends to a `MultisigConfig`-controlled
+    /// address instead of a single destination address, so premine/mint
+    /// outputs can be jointly authorized by an issuing group.
+    fn create_glyph_multisig_output(&self, amount: u64, divisibility: u8,
+                                    config: &MultisigConfig, taproot: bool) -> Result<TxOut, GlyphError> {
+        if amount == 0 {
+            return Ok(TxOut { value: 0, script_pubkey: Script::new() });
+        }
+
+        let multisig_address = self.create_multisig_address(config, taproot)?;
+        let output_value = amount * 10u64.pow(divisibility as u32);
+        Ok(TxOut {
+            value: output_value,
+            script_pubkey: multisig_address.script_pubkey(),
+        })
+    }
+
+    /// Accepts either a bare `block:tx` glyph ID or a checksummed
+    /// `glyph1…` reference produced by `encode_glyph_ref`. The latter is
+    /// preferred wherever a human retypes a glyph ID by hand, since a single
+    /// mistyped character fails the bech32m checksum instead of silently
+    /// resolving to a different glyph.
+    fn parse_glyph_id(&self, glyph_id: &str) -> Result<(u32, u32), GlyphError> {
+        if glyph_id.starts_with(&format!("{}1", GLYPH_REF_HRP)) {
+            let (block_height, tx_index, _) = self.decode_glyph_ref(glyph_id)?;
+            return Ok((block_height, tx_index));
+        }
+
         let parts: Vec<&str> = glyph_id.split(':').collect();
         if parts.len() != 2 {
             return Err(GlyphError::InvalidTransaction(format!("Invalid glyph_id format: {}", glyph_id)));
@@ -560,124 +1896,996 @@ impl GlyphProtocol {
         Ok((block_height, tx_index))
     }
 
-    fn initiate_swap(&self, glyph_id: &str, amount: u64, destination_address: &str,
-        counterparty_pubkey: &str, secret: &str, timelock: u32) -> Result<String, GlyphError> {
-let secret_hash = sha256::Hash::hash(secret.as_bytes());
-let receiver_pubkey = PublicKey::from_str(counterparty_pubkey)
-.map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty pubkey: {}", e)))?;
-let sender_pubkey = self.get_pubkey_from_address(destination_address)?;
+    /// Starts a swap as the initiating party: mints a fresh preimage, locks the
+    /// glyph balance behind an HTLC output, and moves it there with a `T`
+    /// glyphstone so the balance tracker follows it. The preimage is persisted
+    /// locally (we're the only side that knows it until we claim or reveal it)
+    /// so `claim_swap`/a watcher can use it later.
+    fn initiate_swap(&self, glyph_id: &str, amount: u64, destination_address: &str,
+        counterparty_pubkey: &str, timelock: u32) -> Result<(String, Vec<u8>), GlyphError> {
+        let preimage = self.random_preimage();
+        let secret_hash = hash160::Hash::hash(&preimage);
+        let receiver_pubkey = PublicKey::from_str(counterparty_pubkey)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty pubkey: {}", e)))?;
+        let sender_pubkey = self.get_pubkey_from_address(destination_address)?;
+
+        let htlc_script = self.create_htlc_script(&receiver_pubkey, &sender_pubkey, secret_hash.as_inner(), timelock)?;
+        let htlc_output = TxOut {
+            value: amount,
+            script_pubkey: htlc_script,
+        };
+
+        let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
+        let mut glyphstone_data = vec![b'T'];
+        glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(amount));
+        glyphstone_data.extend_from_slice(&self.encode_varint(1)); // HTLC output lands at index 1
+        let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
+
+        let change_address = self.backend.new_change_address()?;
+        let txid = self.construct_and_broadcast_transaction(glyphstone_output, Some(htlc_output), Some(change_address.as_str()), 1, true, false)?;
+
+        self.persist_swap(&SwapRecord {
+            role: SwapRole::Initiator,
+            htlc_txid: txid.clone(),
+            vout: 1,
+            amount,
+            secret_hash: secret_hash.as_inner().to_vec(),
+            preimage: Some(preimage.to_vec()),
+            timelock,
+            counterparty_pubkey: counterparty_pubkey.to_string(),
+            own_pubkey: sender_pubkey.to_string(),
+            destination_address: destination_address.to_string(),
+            peer_htlc_txid: None,
+            peer_vout: None,
+            peer_timelock: None,
+        })?;
+
+        Ok((txid, preimage.to_vec()))
+    }
+
+    /// Locks the matching leg of the swap once the counterparty has shared
+    /// their `secret_hash`. We don't know the preimage yet — that only shows
+    /// up when the counterparty claims their side, at which point a watcher
+    /// can extract it from the spending witness.
+    fn participate_in_swap(&self, glyph_id: &str, amount: u64,
+                  counterparty_htlc_details: &HashMap<String, String>,
+                  destination_address: &str) -> Result<String, GlyphError> {
+        let secret_hash = hex::decode(&counterparty_htlc_details["secret_hash"])
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid secret hash: {}", e)))?;
+        let receiver_pubkey = PublicKey::from_str(&counterparty_htlc_details["receiver_pubkey"])
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid receiver pubkey: {}", e)))?;
+        let sender_pubkey = self.get_pubkey_from_address(destination_address)?;
+        let timelock: u32 = counterparty_htlc_details["timelock"].parse()
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid timelock: {}", e)))?;
+
+        let htlc_script = self.create_htlc_script(&receiver_pubkey, &sender_pubkey, &secret_hash, timelock)?;
+        let htlc_output = TxOut {
+            value: amount,
+            script_pubkey: htlc_script,
+        };
+
+        let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
+        let mut glyphstone_data = vec![b'T'];
+        glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(amount));
+        glyphstone_data.extend_from_slice(&self.encode_varint(1));
+        let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
+
+        let change_address = self.backend.new_change_address()?;
+        let txid = self.construct_and_broadcast_transaction(glyphstone_output, Some(htlc_output), Some(change_address.as_str()), 1, true, false)?;
+
+        self.persist_swap(&SwapRecord {
+            role: SwapRole::Participant,
+            htlc_txid: txid.clone(),
+            vout: 1,
+            amount,
+            secret_hash,
+            preimage: None,
+            timelock,
+            counterparty_pubkey: counterparty_htlc_details["receiver_pubkey"].clone(),
+            own_pubkey: sender_pubkey.to_string(),
+            destination_address: destination_address.to_string(),
+            // The initiator's original HTLC, if they've shared it — the leg
+            // that pays us once we learn the preimage. `link_swap` fills
+            // these in later if they aren't known yet at participation time.
+            peer_htlc_txid: counterparty_htlc_details.get("htlc_txid").cloned(),
+            peer_vout: counterparty_htlc_details.get("vout")
+                .map(|v| v.parse())
+                .transpose()
+                .map_err(|_| GlyphError::InvalidTransaction("Invalid peer vout".to_string()))?,
+            peer_timelock: counterparty_htlc_details.get("peer_timelock")
+                .map(|v| v.parse())
+                .transpose()
+                .map_err(|_| GlyphError::InvalidTransaction("Invalid peer timelock".to_string()))?,
+        })?;
+
+        Ok(txid)
+    }
+
+    /// Routes a Glyph transfer through one or more intermediary makers so
+    /// no single on-chain transaction links the original sender to the
+    /// final receiver — a CoinSwap carrying a Glyph balance alongside the
+    /// BTC. `hops` is the ordered path, each entry `(counterparty_pubkey,
+    /// amount, fee)`, where `amount` is what lands in that hop's HTLC
+    /// (already net of `fee`, so amounts strictly decrease hop over hop
+    /// and the last entry is the final receiver).
+    ///
+    /// Written from the perspective of a single caller funding the whole
+    /// route up front (e.g. testing it end-to-end, or a liquidity-
+    /// providing router fronting every hop) — the same single-wallet
+    /// assumption `initiate_swap` already makes for a plain two-party
+    /// swap, just repeated per hop with a fresh refund key each time for
+    /// better on-chain unlinkability between hops.
+    ///
+    /// Every hop shares one `secret_hash`, and hop `i`'s `peer_htlc_txid`/
+    /// `peer_vout` point at hop `i + 1`, so `SwapWatcher` needs no changes
+    /// to walk the whole chain: only the final hop's record starts with
+    /// `preimage` filled in, and once it's claimed the watcher's existing
+    /// `find_spending_tx` + `extract_preimage` logic discovers it from
+    /// the chain and cascades claimability back up the route one hop at
+    /// a time. Each hop's timelock is `base_timelock - timelock_step *
+    /// hop_index`, so downstream hops expire first — if the route stalls,
+    /// the last hop becomes refundable first and refunding walks back
+    /// upstream in step with that same cascade.
+    fn route_swap(&self, glyph_id: &str, hops: &[(PublicKey, u64, u64)],
+                  base_timelock: u32, timelock_step: u32) -> Result<Vec<(String, u32)>, GlyphError> {
+        if hops.is_empty() {
+            return Err(GlyphError::InvalidTransaction("route_swap requires at least one hop".to_string()));
+        }
+
+        let preimage = self.random_preimage();
+        let secret_hash = hash160::Hash::hash(&preimage);
+
+        struct BroadcastHop {
+            txid: String,
+            timelock: u32,
+            amount: u64,
+            receiver_pubkey: String,
+            sender_pubkey: String,
+            refund_address: String,
+        }
+        let mut broadcast_hops = Vec::with_capacity(hops.len());
+
+        for (hop_index, (receiver_pubkey, amount, _fee)) in hops.iter().enumerate() {
+            let amount = *amount;
+            let timelock = base_timelock.saturating_sub(timelock_step * hop_index as u32);
+            let refund_address = self.backend.new_change_address()?;
+            let sender_pubkey = self.get_pubkey_from_address(&refund_address)?;
+
+            let htlc_script = self.create_htlc_script(receiver_pubkey, &sender_pubkey, secret_hash.as_inner(), timelock)?;
+            let htlc_output = TxOut { value: amount, script_pubkey: htlc_script };
+
+            let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
+            let mut glyphstone_data = vec![b'T'];
+            glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
+            glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
+            glyphstone_data.extend_from_slice(&self.encode_varint(amount));
+            glyphstone_data.extend_from_slice(&self.encode_varint(1)); // HTLC output lands at index 1
+            let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
+
+            let change_address = self.backend.new_change_address()?;
+            let txid = self.construct_and_broadcast_transaction(glyphstone_output, Some(htlc_output), Some(change_address.as_str()), 1, true, false)?;
+
+            broadcast_hops.push(BroadcastHop {
+                txid, timelock, amount,
+                receiver_pubkey: receiver_pubkey.to_string(),
+                sender_pubkey: sender_pubkey.to_string(),
+                refund_address,
+            });
+        }
+
+        for hop_index in 0..broadcast_hops.len() {
+            let next_hop = broadcast_hops.get(hop_index + 1);
+            let hop = &broadcast_hops[hop_index];
+            self.persist_swap(&SwapRecord {
+                role: SwapRole::Initiator,
+                htlc_txid: hop.txid.clone(),
+                vout: 1,
+                amount: hop.amount,
+                secret_hash: secret_hash.as_inner().to_vec(),
+                // Only the final hop starts with the preimage already
+                // known; every upstream hop waits for the watcher to
+                // observe it revealed downstream.
+                preimage: if next_hop.is_none() { Some(preimage.to_vec()) } else { None },
+                timelock: hop.timelock,
+                counterparty_pubkey: hop.receiver_pubkey.clone(),
+                own_pubkey: hop.sender_pubkey.clone(),
+                destination_address: hop.refund_address.clone(),
+                peer_htlc_txid: next_hop.map(|next| next.txid.clone()),
+                peer_vout: next_hop.map(|_| 1),
+                peer_timelock: next_hop.map(|next| next.timelock),
+            })?;
+        }
+
+        Ok(broadcast_hops.into_iter().map(|hop| (hop.txid, hop.timelock)).collect())
+    }
+
+    /// Spends the hashlock leaf of the counterparty's matching HTLC — the
+    /// leg that actually pays us — by revealing `preimage`. `htlc_txid`:
+    /// `vout` here name the counterparty's outpoint, not our own broadcast
+    /// leg: we're that leaf's hashlock receiver, never on our own leg,
+    /// since `self.signer` only ever holds our own keys. Rejects the claim
+    /// outright if the preimage doesn't match the hash the output was
+    /// locked to, rather than letting a bad spend reach the network. A
+    /// cooperative counterparty would instead just key-path spend the
+    /// taproot output directly with both sides' signatures; this is the
+    /// unilateral fallback.
+    fn claim_swap(&self, htlc_txid: &str, vout: u32, preimage: &[u8], destination_address: &str) -> Result<String, GlyphError> {
+        let swap = self.find_swap_by_peer(htlc_txid, vout)?;
+        let peer_timelock = swap.peer_timelock
+            .ok_or_else(|| GlyphError::InvalidTransaction("Swap is not linked to a timelock for this peer HTLC".to_string()))?;
+        if hash160::Hash::hash(preimage).as_inner().to_vec() != swap.secret_hash {
+            return Err(GlyphError::InvalidTransaction("Preimage does not match the HTLC's secret hash".to_string()));
+        }
+
+        let htlc_tx = self.backend.get_transaction(
+            &Txid::from_str(htlc_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid HTLC txid".to_string()))?
+        )?;
+        let htlc_output = htlc_tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No output {} on HTLC transaction {}", vout, htlc_txid)))?;
+
+        // We're the receiver on the counterparty's leg; they're the sender/refund.
+        let receiver_pubkey = PublicKey::from_str(&swap.own_pubkey)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid receiver pubkey in swap record: {}", e)))?;
+        let sender_pubkey = PublicKey::from_str(&swap.counterparty_pubkey)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid sender pubkey in swap record: {}", e)))?;
+        let (hashlock_leaf_script, control_block) =
+            self.htlc_hashlock_spend_info(&receiver_pubkey, &sender_pubkey, &swap.secret_hash, peer_timelock)?;
+
+        let tx_in = TxIn {
+            previous_output: OutPoint::new(Txid::from_str(htlc_txid)?, vout),
+            script_sig: Script::new(),
+            sequence: 0xFFFFFFFF,
+            // Script-path witness for the hashlock leaf: preimage, the
+            // leaf script, and its control block. The signing pass below
+            // prepends the Schnorr signature the leaf's `OP_CHECKSIG` needs.
+            witness: vec![preimage.to_vec(), hashlock_leaf_script.into_bytes(), control_block],
+        };
+
+        let destination_address_obj = Address::from_str(destination_address)?;
+        let tx_out = TxOut {
+            value: htlc_output.value,
+            script_pubkey: destination_address_obj.script_pubkey(),
+        };
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![tx_in],
+            output: vec![tx_out],
+        };
+
+        let signed_tx = self.signer.sign_transaction(&tx)?;
+        let txid = self.backend.broadcast(&signed_tx)?;
+        Ok(txid.to_string())
+    }
+
+    /// Spends the timelock leaf once `nLockTime` has reached the HTLC's
+    /// timelock, returning the funds to the sender.
+    fn refund_swap(&self, htlc_txid: &str, vout: u32, destination_address: &str) -> Result<String, GlyphError> {
+        let swap = self.find_swap(htlc_txid, vout)?;
+        let current_height = self.backend.get_block_count()?;
+        if current_height < swap.timelock {
+            return Err(GlyphError::InvalidTransaction(format!("Timelock {} not yet reached (current height {})", swap.timelock, current_height)));
+        }
+
+        let htlc_tx = self.backend.get_transaction(
+            &Txid::from_str(htlc_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid HTLC txid".to_string()))?
+        )?;
+        let htlc_output = htlc_tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No output {} on HTLC transaction {}", vout, htlc_txid)))?;
+
+        let receiver_pubkey = PublicKey::from_str(&swap.counterparty_pubkey)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid receiver pubkey in swap record: {}", e)))?;
+        let sender_pubkey = PublicKey::from_str(&swap.own_pubkey)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid sender pubkey in swap record: {}", e)))?;
+        let (timelock_leaf_script, control_block) =
+            self.htlc_timelock_spend_info(&receiver_pubkey, &sender_pubkey, &swap.secret_hash, swap.timelock)?;
+
+        let tx_in = TxIn {
+            previous_output: OutPoint::new(Txid::from_str(htlc_txid)?, vout),
+            script_sig: Script::new(),
+            sequence: 0xFFFFFFFE, // non-final so nLockTime is honored
+            // Script-path witness for the timelock leaf: the leaf script
+            // and its control block, with the Schnorr signature prepended
+            // by the signing pass below.
+            witness: vec![timelock_leaf_script.into_bytes(), control_block],
+        };
+
+        let destination_address_obj = Address::from_str(destination_address)?;
+        let tx_out = TxOut {
+            value: htlc_output.value,
+            script_pubkey: destination_address_obj.script_pubkey(),
+        };
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: swap.timelock,
+            input: vec![tx_in],
+            output: vec![tx_out],
+        };
+
+        let signed_tx = self.signer.sign_transaction(&tx)?;
+        let txid = self.backend.broadcast(&signed_tx)?;
+        Ok(txid.to_string())
+    }
+
+    /// Records the counterparty's leg of an already-tracked swap once it's
+    /// known: their outpoint and the timelock they locked it with. This is
+    /// what lets `claim_swap` rebuild that leg's tap leaves to spend it (we're
+    /// its hashlock receiver), and what a `SwapWatcher` watches our own leg
+    /// against to know when to do so.
+    fn link_swap(&self, htlc_txid: &str, vout: u32, peer_htlc_txid: &str, peer_vout: u32, peer_timelock: u32) -> Result<(), GlyphError> {
+        self.update_swap(htlc_txid, vout, |record| {
+            record.peer_htlc_txid = Some(peer_htlc_txid.to_string());
+            record.peer_vout = Some(peer_vout);
+            record.peer_timelock = Some(peer_timelock);
+        })
+    }
+
+    /// The BIP-340 Schnorr "anticipation point" for the oracle signing
+    /// `digit` under `nonce_point`: `R + e*P` where `e` is the usual
+    /// `BIP0340/challenge` tagged hash of `R || P || digit`. Anyone can
+    /// compute this before the oracle attests to anything; only the oracle
+    /// can later produce the scalar `s` with `s*G` equal to it.
+    fn oracle_digit_sig_point(&self, nonce_point: &PublicKey, oracle_pubkey: &PublicKey, digit: u8) -> Result<PublicKey, GlyphError> {
+        let secp = Secp256k1::new();
+        let mut preimage = nonce_point.key.serialize()[1..33].to_vec();
+        preimage.extend_from_slice(&oracle_pubkey.key.serialize()[1..33]);
+        preimage.push(digit);
+        let challenge = self.tagged_hash("BIP0340/challenge", &preimage);
+        let challenge_scalar = SecretKey::from_slice(&challenge)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid oracle challenge: {}", e)))?;
+
+        let tweaked_oracle_point = oracle_pubkey.key.mul_tweak(&secp, &challenge_scalar)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to tweak oracle point: {}", e)))?;
+        let sig_point = nonce_point.key.combine(&tweaked_oracle_point)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to combine oracle signature point: {}", e)))?;
+
+        Ok(PublicKey { compressed: true, key: sig_point })
+    }
+
+    /// The anticipation point for an entire digit prefix: the sum of each
+    /// covered digit's own per-position anticipation point. Once the oracle
+    /// attests to an outcome whose digits start with this prefix, summing
+    /// the corresponding per-digit signature scalars yields the discrete
+    /// log of this same point — the adaptor secret that completes the CET.
+    fn cet_anticipation_point(&self, announcement: &OracleAnnouncement, digit_prefix: &[u8]) -> Result<PublicKey, GlyphError> {
+        if digit_prefix.len() > announcement.nonce_points.len() {
+            return Err(GlyphError::InvalidTransaction("Digit prefix longer than the oracle's announced digits".to_string()));
+        }
+
+        let mut points = Vec::with_capacity(digit_prefix.len());
+        for (i, &digit) in digit_prefix.iter().enumerate() {
+            points.push(self.oracle_digit_sig_point(&announcement.nonce_points[i], &announcement.oracle_pubkey, digit)?.key);
+        }
+
+        let combined = secp256k1::PublicKey::combine_keys(&points.iter().collect::<Vec<_>>())
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to combine prefix anticipation points: {}", e)))?;
+        Ok(PublicKey { compressed: true, key: combined })
+    }
+
+    /// Walks the base-2 trie over `num_digits` digits and returns the
+    /// minimal set of digit-prefixes whose subtrees lie entirely inside
+    /// `[range_start, range_end]` — turning what would be one CET per value
+    /// (`O(2^num_digits)`) into `O(num_digits)` CETs covering the same range.
+    fn cover_range_with_prefixes(&self, range_start: u64, range_end: u64, num_digits: u32) -> Vec<Vec<u8>> {
+        fn walk(node: Vec<u8>, depth: u32, num_digits: u32, range_start: u64, range_end: u64, out: &mut Vec<Vec<u8>>) {
+            let remaining = num_digits - depth;
+            let subtree_start = node.iter().fold(0u64, |acc, &d| (acc << 1) | d as u64) << remaining;
+            let subtree_end = subtree_start | ((1u64 << remaining) - 1);
+
+            if subtree_start >= range_start && subtree_end <= range_end {
+                out.push(node);
+                return;
+            }
+            if subtree_end < range_start || subtree_start > range_end {
+                return;
+            }
+
+            let mut left = node.clone();
+            left.push(0);
+            walk(left, depth + 1, num_digits, range_start, range_end, out);
+            let mut right = node;
+            right.push(1);
+            walk(right, depth + 1, num_digits, range_start, range_end, out);
+        }
+
+        let mut prefixes = Vec::new();
+        walk(Vec::new(), 0, num_digits, range_start, range_end, &mut prefixes);
+        prefixes
+    }
+
+    /// Builds the CETs for a DLC given a piecewise payout curve: each
+    /// `(range_start, range_end, payout_a, payout_b)` entry covers one
+    /// contiguous band of outcomes with a fixed split, and is expanded into
+    /// the minimal set of digit-prefix CETs via `cover_range_with_prefixes`.
+    fn build_dlc_cets(&self, announcement: &OracleAnnouncement, payout_ranges: &[(u64, u64, u64, u64)], num_digits: u32) -> Result<Vec<Cet>, GlyphError> {
+        if announcement.nonce_points.len() != num_digits as usize {
+            return Err(GlyphError::InvalidTransaction(format!(
+                "Oracle announced {} nonce points but the contract uses {} digits", announcement.nonce_points.len(), num_digits
+            )));
+        }
+
+        let mut cets = Vec::new();
+        for &(range_start, range_end, payout_a, payout_b) in payout_ranges {
+            for digit_prefix in self.cover_range_with_prefixes(range_start, range_end, num_digits) {
+                let anticipation_point = self.cet_anticipation_point(announcement, &digit_prefix)?;
+                cets.push(Cet { digit_prefix, payout_a, payout_b, anticipation_point });
+            }
+        }
+        Ok(cets)
+    }
+
+    /// The single CET leaf shared by every outcome: `<counterparty_xonly>
+    /// OP_CHECKSIG`. Earlier this leaf was tweaked per-CET by that CET's
+    /// anticipation point, but nobody ever holds the private key behind a
+    /// tweaked aggregate point, so that leaf could never actually be
+    /// spent. The oracle dependency now lives entirely in the signature:
+    /// `presign_cet` hands out an adaptor signature over this same leaf
+    /// encrypted under each CET's anticipation point, and only the
+    /// oracle's attestation lets `execute_dlc_cet` decrypt the one
+    /// matching the attested outcome. See `sign_cet_adaptor`.
+    fn dlc_cet_leaf_script(&self, counterparty_pubkey: &PublicKey) -> Script {
+        let x_only = &counterparty_pubkey.key.serialize()[1..33];
+        Script::new().push_slice(x_only).push_opcode(OP_CHECKSIG)
+    }
+
+    /// The CET leaf plus the control block needed to spend it: the same
+    /// two-leaf (CET + timelocked refund) tree `create_dlc_funding_script`
+    /// commits to, mirroring `htlc_hashlock_spend_info`'s shape. The
+    /// refund leaf stays keyed to the musig-aggregate internal key, same
+    /// as before this leaf was redesigned — out of scope here.
+    fn dlc_cet_spend_info(&self, pubkey_a: &PublicKey, pubkey_b: &PublicKey, timelock: u32) -> Result<(Script, Vec<u8>), GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(pubkey_a, pubkey_b)?;
+        let cet_leaf = self.dlc_cet_leaf_script(pubkey_b);
+        let refund_leaf = self.htlc_timelock_leaf_script(&internal_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&cet_leaf), self.tap_leaf_hash(&refund_leaf));
+        let control_block = self.htlc_leaf_control_block(&internal_pubkey, merkle_root, self.tap_leaf_hash(&refund_leaf))?;
+        Ok((cet_leaf, control_block))
+    }
+
+    /// The timelocked refund leaf plus the control block needed to spend
+    /// it, from the same two-leaf tree as `dlc_cet_spend_info`.
+    fn dlc_refund_spend_info(&self, pubkey_a: &PublicKey, pubkey_b: &PublicKey, timelock: u32) -> Result<(Script, Vec<u8>), GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(pubkey_a, pubkey_b)?;
+        let cet_leaf = self.dlc_cet_leaf_script(pubkey_b);
+        let refund_leaf = self.htlc_timelock_leaf_script(&internal_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&cet_leaf), self.tap_leaf_hash(&refund_leaf));
+        let control_block = self.htlc_leaf_control_block(&internal_pubkey, merkle_root, self.tap_leaf_hash(&cet_leaf))?;
+        Ok((refund_leaf, control_block))
+    }
+
+    /// Both parties' Glyphs are locked into this taproot output: the
+    /// internal key is the `musig_aggregate_pubkey` of both parties, and
+    /// the script tree commits the shared CET leaf plus a timelocked
+    /// refund leaf — the same cooperative-internal-key-with-fallback-leaves
+    /// shape `create_htlc_script` uses for swaps. One leaf now covers every
+    /// CET, so unlike before this no longer depends on the CET list itself.
+    fn create_dlc_funding_script(&self, pubkey_a: &PublicKey, pubkey_b: &PublicKey, timelock: u32) -> Result<Script, GlyphError> {
+        let internal_pubkey = self.musig_aggregate_pubkey(pubkey_a, pubkey_b)?;
+        let cet_leaf = self.dlc_cet_leaf_script(pubkey_b);
+        let refund_leaf = self.htlc_timelock_leaf_script(&internal_pubkey, timelock);
+        let merkle_root = self.tap_branch_hash(self.tap_leaf_hash(&cet_leaf), self.tap_leaf_hash(&refund_leaf));
+        let (tweaked_xonly, _) = self.tweak_taproot_key(&internal_pubkey, Some(merkle_root))?;
+        Ok(Address::p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(tweaked_xonly), self.network).script_pubkey())
+    }
+
+    /// Locks `amount` Glyphs from `glyph_id` into the DLC's taproot funding
+    /// output and returns the funding txid alongside the CETs either party
+    /// can later complete once the oracle attests.
+    fn initiate_dlc(&self, glyph_id: &str, amount: u64, own_pubkey: &PublicKey, counterparty_pubkey: &PublicKey,
+                    announcement: &OracleAnnouncement, payout_ranges: &[(u64, u64, u64, u64)], num_digits: u32,
+                    timelock: u32, change_address: Option<&str>) -> Result<(String, Vec<Cet>), GlyphError> {
+        let cets = self.build_dlc_cets(announcement, payout_ranges, num_digits)?;
+
+        let funding_script = self.create_dlc_funding_script(own_pubkey, counterparty_pubkey, timelock)?;
+        let funding_output = TxOut { value: amount, script_pubkey: funding_script };
+
+        let (block_height, tx_index) = self.parse_glyph_id(glyph_id)?;
+        let mut glyphstone_data = vec![b'T'];
+        glyphstone_data.extend_from_slice(&self.encode_varint(block_height as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(tx_index as u64));
+        glyphstone_data.extend_from_slice(&self.encode_varint(amount));
+        glyphstone_data.extend_from_slice(&self.encode_varint(1)); // funding output lands at index 1
+        let glyphstone_output = self.create_glyphstone_output(&glyphstone_data);
+
+        let txid = self.construct_and_broadcast_transaction(glyphstone_output, Some(funding_output), change_address, 1, true, false)?;
+        Ok((txid, cets))
+    }
+
+    /// The unsigned CET transaction for `cet`, spending the DLC funding
+    /// output to `destination_address`. Shared by `presign_cet` and
+    /// `execute_dlc_cet` so both always sign and complete the exact same
+    /// sighash.
+    fn build_cet_transaction(&self, funding_txid: &str, vout: u32, funding_output: &TxOut, cet: &Cet, destination_address: &str) -> Result<Transaction, GlyphError> {
+        let tx_in = TxIn {
+            previous_output: OutPoint::new(Txid::from_str(funding_txid)?, vout),
+            script_sig: Script::new(),
+            sequence: 0xFFFFFFFF,
+            witness: vec![],
+        };
+
+        let total_payout = cet.payout_a + cet.payout_b;
+        let destination_address_obj = Address::from_str(destination_address)?;
+        let tx_out = TxOut {
+            value: funding_output.value.min(total_payout),
+            script_pubkey: destination_address_obj.script_pubkey(),
+        };
+
+        Ok(Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![tx_in],
+            output: vec![tx_out],
+        })
+    }
+
+    /// The BIP-341 "default" sighash (key_version 0, no annex, implicit
+    /// `SIGHASH_DEFAULT`) for a script-path spend of `leaf_script` in a
+    /// single-input transaction — the digest every leaf's `OP_CHECKSIG` in
+    /// this file actually signs. Specialized to one input since that's all
+    /// `build_cet_transaction`, `claim_swap`, and `refund_swap` ever build.
+    fn taproot_script_path_sighash(&self, tx: &Transaction, prevout: &TxOut, leaf_script: &Script) -> [u8; 32] {
+        let mut sig_msg = vec![0x00u8, 0x00u8]; // epoch, then hash_type (SIGHASH_DEFAULT)
+        sig_msg.extend_from_slice(&tx.version.to_le_bytes());
+        sig_msg.extend_from_slice(&tx.lock_time.to_le_bytes());
+
+        let sha_prevouts = sha256::Hash::hash(&bitcoin::consensus::encode::serialize(&tx.input[0].previous_output));
+        sig_msg.extend_from_slice(sha_prevouts.as_inner());
+
+        let sha_amounts = sha256::Hash::hash(&prevout.value.to_le_bytes());
+        sig_msg.extend_from_slice(sha_amounts.as_inner());
+
+        let sha_scriptpubkeys = sha256::Hash::hash(&bitcoin::consensus::encode::serialize(&prevout.script_pubkey));
+        sig_msg.extend_from_slice(sha_scriptpubkeys.as_inner());
+
+        let sha_sequences = sha256::Hash::hash(&tx.input[0].sequence.to_le_bytes());
+        sig_msg.extend_from_slice(sha_sequences.as_inner());
+
+        let encoded_outputs: Vec<u8> = tx.output.iter().flat_map(|o| bitcoin::consensus::encode::serialize(o)).collect();
+        let sha_outputs = sha256::Hash::hash(&encoded_outputs);
+        sig_msg.extend_from_slice(sha_outputs.as_inner());
+
+        sig_msg.push(0x02); // spend_type: script path (ext_flag=1), no annex
+        sig_msg.extend_from_slice(&0u32.to_le_bytes()); // input_index: always 0, our only input
+
+        sig_msg.extend_from_slice(&self.tap_leaf_hash(leaf_script));
+        sig_msg.push(0x00); // key_version
+        sig_msg.extend_from_slice(&0xFFFFFFFFu32.to_le_bytes()); // codesep_pos: none
+
+        self.tagged_hash("TapSighash", &sig_msg)
+    }
+
+    /// Schnorr-adaptor-signs `leaf_script`'s sighash under `pubkey`,
+    /// encrypted so the result only becomes a valid signature once someone
+    /// learns the discrete log of `adaptor_point` — the oracle attestation
+    /// a DLC CET is waiting on. Returns `(r, s_prime)`; `presign_cet` is
+    /// the only caller, and `complete_adaptor_signature` is how the other
+    /// side later turns this into a real signature.
+    ///
+    /// Standard two-step adaptor-sig construction: pick a nonce `k`,
+    /// encrypt its point as `R' = kG + adaptor_point`, then sign against
+    /// `R'` instead of `R` (`s' = k + e*x mod n`, where `e` hashes `R'`).
+    /// We don't control `adaptor_point`'s own parity, so unlike a plain
+    /// Schnorr nonce `R'` isn't guaranteed even-Y on the first try — so we
+    /// just try a handful of nonces until one lands even, each
+    /// independently about as likely to as not.
+    fn sign_cet_adaptor(&self, tx: &Transaction, prevout: &TxOut, leaf_script: &Script, pubkey: &PublicKey, adaptor_point: &PublicKey) -> Result<([u8; 32], [u8; 32]), GlyphError> {
+        let secp = Secp256k1::new();
+        let secret = self.signer.export_secret_for_pubkey(pubkey, self.network)?;
+        let secret_bytes: [u8; 32] = secret.as_ref()[..32].try_into().unwrap();
+        let x = if pubkey.key.serialize()[0] == 0x03 {
+            self.scalar_negate_mod_n(&secret_bytes)
+        } else {
+            secret_bytes
+        };
+
+        let sighash = self.taproot_script_path_sighash(tx, prevout, leaf_script);
+        let pubkey_xonly = &pubkey.key.serialize()[1..33];
+
+        for attempt in 0u8..8 {
+            let mut nonce_preimage = secret_bytes.to_vec();
+            nonce_preimage.extend_from_slice(&sighash);
+            nonce_preimage.push(attempt);
+            let k_bytes = self.tagged_hash("GlyphDlcNonce", &nonce_preimage);
+            let k = SecretKey::from_slice(&k_bytes)
+                .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid CET adaptor nonce: {}", e)))?;
+
+            let nonce_point = secp256k1::PublicKey::from_secret_key(&secp, &k);
+            let encrypted_point = nonce_point.combine(&adaptor_point.key)
+                .map_err(|e| GlyphError::InvalidTransaction(format!("Failed to encrypt CET adaptor nonce: {}", e)))?;
+
+            if encrypted_point.serialize()[0] == 0x02 {
+                let r: [u8; 32] = encrypted_point.serialize()[1..33].try_into().unwrap();
+
+                let mut challenge_preimage = r.to_vec();
+                challenge_preimage.extend_from_slice(pubkey_xonly);
+                challenge_preimage.extend_from_slice(&sighash);
+                let e = self.tagged_hash("BIP0340/challenge", &challenge_preimage);
+
+                let e_x = self.scalar_mul_mod_n(&e, &x);
+                let s_prime = self.scalar_add_mod_n(&k_bytes, &e_x);
+                return Ok((r, s_prime));
+            }
+        }
+
+        Err(GlyphError::InvalidTransaction("Failed to find an even-Y CET adaptor nonce after 8 attempts".to_string()))
+    }
+
+    /// Decrypts a `sign_cet_adaptor` output `(r, s_prime)` with the
+    /// now-revealed `adaptor_secret`, producing the completed 64-byte
+    /// Schnorr signature `(r, s' + adaptor_secret mod n)` that satisfies
+    /// the CET leaf's `OP_CHECKSIG`.
+    fn complete_adaptor_signature(&self, r: &[u8; 32], s_prime: &[u8; 32], adaptor_secret: &[u8; 32]) -> [u8; 64] {
+        let s = self.scalar_add_mod_n(s_prime, adaptor_secret);
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(r);
+        signature[32..].copy_from_slice(&s);
+        signature
+    }
+
+    /// Pre-signs CET `cets[cet_index]` for the counterparty: an adaptor
+    /// signature over the CET leaf's sighash, encrypted under that CET's
+    /// own anticipation point. `own_pubkey`/`counterparty_pubkey` must be
+    /// passed in the same fixed roles `initiate_dlc` used regardless of
+    /// who calls this — the CET leaf is always keyed to
+    /// `counterparty_pubkey`, so it's always that party's signer this
+    /// calls into. Returns `(r, s_prime)`, the pair `execute_dlc_cet` later
+    /// needs alongside the oracle's attestation to complete a real
+    /// signature.
+    fn presign_cet(&self, funding_txid: &str, vout: u32, own_pubkey: &PublicKey, counterparty_pubkey: &PublicKey,
+                   cets: &[Cet], cet_index: usize, timelock: u32, destination_address: &str) -> Result<([u8; 32], [u8; 32]), GlyphError> {
+        let cet = cets.get(cet_index)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No CET at index {}", cet_index)))?;
+
+        let funding_tx = self.backend.get_transaction(
+            &Txid::from_str(funding_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid DLC funding txid".to_string()))?
+        )?;
+        let funding_output = funding_tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No output {} on DLC funding transaction {}", vout, funding_txid)))?;
+
+        let tx = self.build_cet_transaction(funding_txid, vout, funding_output, cet, destination_address)?;
+        let (cet_leaf_script, _) = self.dlc_cet_spend_info(own_pubkey, counterparty_pubkey, timelock)?;
+
+        self.sign_cet_adaptor(&tx, funding_output, &cet_leaf_script, counterparty_pubkey, &cet.anticipation_point)
+    }
+
+    /// Completes and broadcasts CET `cets[cet_index]` once the oracle has
+    /// attested to an outcome starting with that CET's `digit_prefix`.
+    /// `counterparty_adaptor_sig` is the `(r, s_prime)` pair `presign_cet`
+    /// produced for this same CET; `adaptor_secret` must be the discrete
+    /// log of `cets[cet_index].anticipation_point` — the sum of the
+    /// oracle's revealed per-digit signature scalars for that prefix — and
+    /// is what decrypts that pair into a signature the leaf's
+    /// `OP_CHECKSIG` accepts. We check it against the anticipation point
+    /// up front so a wrong or stale attestation fails before we touch the
+    /// adaptor signature at all.
+    fn execute_dlc_cet(&self, funding_txid: &str, vout: u32, own_pubkey: &PublicKey, counterparty_pubkey: &PublicKey,
+                       cets: &[Cet], cet_index: usize, timelock: u32, counterparty_adaptor_sig: &([u8; 32], [u8; 32]),
+                       adaptor_secret: &[u8; 32], destination_address: &str) -> Result<String, GlyphError> {
+        let cet = cets.get(cet_index)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No CET at index {}", cet_index)))?;
+        let secp = Secp256k1::new();
+        let claimed_secret = SecretKey::from_slice(adaptor_secret)
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid adaptor secret: {}", e)))?;
+        if secp256k1::PublicKey::from_secret_key(&secp, &claimed_secret) != cet.anticipation_point.key {
+            return Err(GlyphError::InvalidTransaction("Adaptor secret does not match this CET's anticipation point".to_string()));
+        }
+
+        let funding_tx = self.backend.get_transaction(
+            &Txid::from_str(funding_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid DLC funding txid".to_string()))?
+        )?;
+        let funding_output = funding_tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No output {} on DLC funding transaction {}", vout, funding_txid)))?;
+
+        let (cet_leaf_script, control_block) = self.dlc_cet_spend_info(own_pubkey, counterparty_pubkey, timelock)?;
+        let mut tx = self.build_cet_transaction(funding_txid, vout, funding_output, cet, destination_address)?;
+
+        let (r, s_prime) = counterparty_adaptor_sig;
+        let signature = self.complete_adaptor_signature(r, s_prime, adaptor_secret);
+        // Script-path witness for the CET leaf: the completed Schnorr
+        // signature, the leaf script, and its control block. Already fully
+        // assembled, so this is broadcast directly rather than going back
+        // through `self.signer.sign_transaction`.
+        tx.input[0].witness = vec![signature.to_vec(), cet_leaf_script.into_bytes(), control_block];
+
+        let signed_tx = bitcoin::consensus::encode::serialize(&tx);
+        let txid = self.backend.broadcast(&signed_tx)?;
+        Ok(txid.to_string())
+    }
+
+    /// Spends the funding output's timelocked refund leaf once `timelock`
+    /// has passed without an oracle attestation, paying the whole funding
+    /// output to `destination_address` — the DLC analogue of `refund_swap`.
+    fn refund_dlc(&self, funding_txid: &str, vout: u32, own_pubkey: &PublicKey, counterparty_pubkey: &PublicKey,
+                  timelock: u32, destination_address: &str) -> Result<String, GlyphError> {
+        let current_height = self.backend.get_block_count()?;
+        if current_height < timelock {
+            return Err(GlyphError::InvalidTransaction(format!("Timelock {} not yet reached (current height {})", timelock, current_height)));
+        }
 
-let htlc_script = self.create_htlc_script(&receiver_pubkey, &sender_pubkey, secret_hash.as_inner(), timelock);
+        let funding_tx = self.backend.get_transaction(
+            &Txid::from_str(funding_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid DLC funding txid".to_string()))?
+        )?;
+        let funding_output = funding_tx.output.get(vout as usize)
+            .ok_or_else(|| GlyphError::InvalidTransaction(format!("No output {} on DLC funding transaction {}", vout, funding_txid)))?;
 
-let htlc_output = TxOut {
-value: amount,
-script_pubkey: htlc_script,
-};
+        let (refund_leaf_script, control_block) = self.dlc_refund_spend_info(own_pubkey, counterparty_pubkey, timelock)?;
 
-self.construct_and_broadcast_transaction(htlc_output, None, Some(self.rpc_client.get_new_address(None, None)?.to_string().as_str()), 1, true)
-}
+        let tx_in = TxIn {
+            previous_output: OutPoint::new(Txid::from_str(funding_txid)?, vout),
+            script_sig: Script::new(),
+            sequence: 0xFFFFFFFE, // non-final so nLockTime is honored
+            witness: vec![refund_leaf_script.into_bytes(), control_block],
+        };
 
-fn participate_in_swap(&self, glyph_id: &str, amount: u64, 
-              counterparty_htlc_details: &HashMap<String, String>,
-              destination_address: &str) -> Result<String, GlyphError> {
-let secret_hash = hex::decode(&counterparty_htlc_details["secret_hash"])
-.map_err(|e| GlyphError::InvalidTransaction(format!("Invalid secret hash: {}", e)))?;
-let receiver_pubkey = PublicKey::from_str(&counterparty_htlc_details["receiver_pubkey"])
-.map_err(|e| GlyphError::InvalidTransaction(format!("Invalid receiver pubkey: {}", e)))?;
-let sender_pubkey = self.get_pubkey_from_address(destination_address)?;
-let timelock: u32 = counterparty_htlc_details["timelock"].parse()
-.map_err(|e| GlyphError::InvalidTransaction(format!("Invalid timelock: {}", e)))?;
+        let destination_address_obj = Address::from_str(destination_address)?;
+        let tx_out = TxOut {
+            value: funding_output.value,
+            script_pubkey: destination_address_obj.script_pubkey(),
+        };
 
-let htlc_script = self.create_htlc_script(&receiver_pubkey, &sender_pubkey, &secret_hash, timelock);
+        let tx = Transaction {
+            version: 2,
+            lock_time: timelock,
+            input: vec![tx_in],
+            output: vec![tx_out],
+        };
 
-let htlc_output = TxOut {
-value: amount,
-script_pubkey: htlc_script,
-};
+        let signed_tx = self.signer.sign_transaction(&tx)?;
+        let txid = self.backend.broadcast(&signed_tx)?;
+        Ok(txid.to_string())
+    }
 
-self.construct_and_broadcast_transaction(htlc_output, None, Some(self.rpc_client.get_new_address(None, None)?.to_string().as_str()), 1, true)
+    fn get_pubkey_from_address(&self, address: &str) -> Result<PublicKey, GlyphError> {
+        self.backend.pubkey_for_address(address)
+    }
 }
 
-fn claim_glyph(&self, htlc_txid: &str, secret: &str, destination_address: &str) -> Result<String, GlyphError> {
-let htlc_tx = self.rpc_client.get_transaction(
-&Txid::from_str(htlc_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid HTLC txid".to_string()))?
-)?;
-
-let htlc_output = htlc_tx.vout.iter()
-.find(|output| output.script_pub_key.asm.contains("OP_HASH160"))
-.ok_or_else(|| GlyphError::InvalidTransaction("HTLC output not found".to_string()))?;
+/// How far back to scan full blocks for a spend of a tracked outpoint when
+/// it isn't sitting in the mempool. A day of blocks is enough slack for a
+/// watcher that was offline for a while without scanning the whole chain.
+const SWAP_WATCH_SCAN_DEPTH: u64 = 144;
+
+/// Polls the chain on behalf of a `GlyphProtocol` so pending swaps don't
+/// require a human at the keyboard: it claims as soon as a counterparty's
+/// spend reveals the preimage we need, and refunds once a timelock expires
+/// with nothing claimed. All state lives in the same swap store
+/// `initiate_swap`/`participate_in_swap` already write to, so restarting
+/// the watcher just means reloading and re-scanning from there.
+struct SwapWatcher<'a> {
+    protocol: &'a GlyphProtocol,
+    poll_interval_secs: u64,
+}
 
-let secret_bytes = secret.as_bytes();
-let claim_script = Script::new()
-.push_slice(secret_bytes)
-.push_opcode(OP_TRUE);
+impl<'a> SwapWatcher<'a> {
+    fn new(protocol: &'a GlyphProtocol, poll_interval_secs: u64) -> Self {
+        SwapWatcher { protocol, poll_interval_secs }
+    }
 
-let tx_in = TxIn {
-previous_output: OutPoint::new(Txid::from_str(htlc_txid)?, htlc_output.n),
-script_sig: claim_script,
-sequence: 0xFFFFFFFF,
-witness: vec![],
-};
+    /// A tracked swap's own leg is spendable via only two branches: the
+    /// hashlock branch (pushes the preimage) or the timelock branch (pushes
+    /// nothing). So once the output is gone, whether its spending
+    /// witness carries a preimage tells us which branch ran. A
+    /// hashlock spend of our own leg means the counterparty just revealed
+    /// the preimage we need to go claim *their* leg in turn — `Claimable`
+    /// until we've done that, `Claimed` once `peer_already_claimed` says we
+    /// have.
+    fn status(&self, record: &SwapRecord) -> Result<SwapStatus, GlyphError> {
+        let current_height = self.protocol.backend.get_block_count()?;
+        let txid = Txid::from_str(&record.htlc_txid)
+            .map_err(|_| GlyphError::InvalidTransaction("Invalid HTLC txid in swap store".to_string()))?;
+        let own_outpoint = OutPoint::new(txid, record.vout);
+        let still_unspent = self.protocol.backend.get_tx_out(&txid, record.vout)?.is_some();
+
+        if !still_unspent {
+            let spent_via_hashlock = self.find_spending_tx(&own_outpoint)?
+                .map_or(false, |spending_tx| Self::extract_preimage(&spending_tx, &own_outpoint).is_some());
+            if !spent_via_hashlock {
+                return Ok(SwapStatus::Refunded);
+            }
+            return Ok(if self.peer_already_claimed(record)? { SwapStatus::Claimed } else { SwapStatus::Claimable });
+        }
+        if current_height >= record.timelock {
+            return Ok(SwapStatus::Refundable);
+        }
+        if record.preimage.is_some() && !self.peer_already_claimed(record)? {
+            return Ok(SwapStatus::Claimable);
+        }
+        Ok(SwapStatus::Pending)
+    }
 
-let destination_address_obj = Address::from_str(destination_address)?;
-let tx_out = TxOut {
-value: htlc_output.value.to_sat(),
-script_pubkey: destination_address_obj.script_pubkey(),
-};
+    /// Whether the counterparty's linked leg (the one that pays us) has
+    /// already been spent, i.e. we've claimed it. Also true when nothing's
+    /// linked yet, since there's nothing for us to claim until it is —
+    /// keeps `status` from reporting `Claimable` forever after a successful
+    /// auto-claim, or before `link_swap` has run.
+    fn peer_already_claimed(&self, record: &SwapRecord) -> Result<bool, GlyphError> {
+        match (&record.peer_htlc_txid, record.peer_vout) {
+            (Some(peer_txid), Some(peer_vout)) => {
+                let txid = Txid::from_str(peer_txid)
+                    .map_err(|_| GlyphError::InvalidTransaction("Invalid peer HTLC txid in swap store".to_string()))?;
+                Ok(self.protocol.backend.get_tx_out(&txid, peer_vout)?.is_none())
+            }
+            _ => Ok(true),
+        }
+    }
 
-let tx = Transaction {
-version: 2,
-lock_time: 0,
-input: vec![tx_in],
-output: vec![tx_out],
-};
+    /// Looks for a transaction spending `outpoint`, checking the mempool
+    /// first and falling back to the last `SWAP_WATCH_SCAN_DEPTH` blocks.
+    /// `ElectrumBackend` can't enumerate either (no indexer extension), so
+    /// rather than letting that failure propagate out through `poll_once`
+    /// and `run` and kill the whole watch loop, we log it and treat it the
+    /// same as a clean miss — against that backend this degrades to "the
+    /// watcher can't auto-detect a hashlock reveal," not a crash.
+    fn find_spending_tx(&self, outpoint: &OutPoint) -> Result<Option<Transaction>, GlyphError> {
+        match self.protocol.backend.get_mempool_transactions() {
+            Ok(mempool_txs) => {
+                if let Some(tx) = mempool_txs.into_iter().find(|tx| tx.input.iter().any(|input| input.previous_output == *outpoint)) {
+                    return Ok(Some(tx));
+                }
+            }
+            Err(e) => eprintln!("Watcher: backend cannot enumerate the mempool, skipping: {}", e),
+        }
 
-let signed_tx = self.rpc_client.sign_raw_transaction_with_wallet(&tx, None, None)?;
-let txid = self.rpc_client.send_raw_transaction(&signed_tx.hex)?;
-Ok(txid.to_string())
-}
+        let tip = self.protocol.backend.get_block_count()? as u64;
+        let scan_from = tip.saturating_sub(SWAP_WATCH_SCAN_DEPTH);
+        for height in (scan_from..=tip).rev() {
+            match self.protocol.backend.get_block_transactions(height as u32) {
+                Ok(block_txs) => {
+                    if let Some(tx) = block_txs.into_iter().find(|tx| tx.input.iter().any(|input| input.previous_output == *outpoint)) {
+                        return Ok(Some(tx));
+                    }
+                }
+                Err(e) => {
+                    // Every other height will fail the exact same way, so
+                    // there's no point burning SWAP_WATCH_SCAN_DEPTH log
+                    // lines on a backend that just can't do this.
+                    eprintln!("Watcher: backend cannot enumerate block transactions, skipping: {}", e);
+                    break;
+                }
+            }
+        }
+        Ok(None)
+    }
 
-fn refund_glyph(&self, htlc_txid: &str, destination_address: &str) -> Result<String, GlyphError> {
-let htlc_tx = self.rpc_client.get_transaction(
-&Txid::from_str(htlc_txid).map_err(|_| GlyphError::InvalidTransaction("Invalid HTLC txid".to_string()))?
-)?;
+    /// Pulls the preimage out of a hashlock-leaf claim's witness (see
+    /// `claim_swap`: `[sig, preimage, leaf_script, control_block]`) — the
+    /// revealed secret we were waiting on. A timelock-leaf refund's witness
+    /// is one item shorter (`[sig, leaf_script, control_block]`, no
+    /// preimage slot), so the stack length alone tells the two apart.
+    fn extract_preimage(spending_tx: &Transaction, outpoint: &OutPoint) -> Option<Vec<u8>> {
+        let input = spending_tx.input.iter().find(|input| input.previous_output == *outpoint)?;
+        if input.witness.len() == 4 {
+            Some(input.witness[1].clone())
+        } else {
+            None
+        }
+    }
 
-let htlc_output = htlc_tx.vout.iter()
-.find(|output| output.script_pub_key.asm.contains("OP_HASH160"))
-.ok_or_else(|| GlyphError::InvalidTransaction("HTLC output not found".to_string()))?;
+    /// One pass over every tracked swap: claim what's claimable, refund
+    /// what's refundable, and report the rest so a caller can print status.
+    fn poll_once(&self) -> Result<Vec<(SwapRecord, SwapStatus)>, GlyphError> {
+        let mut results = Vec::new();
+        for mut record in self.protocol.load_swaps()? {
+            let mut status = self.status(&record)?;
+
+            if status == SwapStatus::Claimable && record.preimage.is_none() {
+                let own_outpoint = OutPoint::new(Txid::from_str(&record.htlc_txid)?, record.vout);
+                if let Some(spending_tx) = self.find_spending_tx(&own_outpoint)? {
+                    if let Some(preimage) = Self::extract_preimage(&spending_tx, &own_outpoint) {
+                        self.protocol.update_swap(&record.htlc_txid, record.vout, |r| r.preimage = Some(preimage.clone()))?;
+                        record.preimage = Some(preimage);
+                    }
+                }
+            }
 
-let refund_script = Script::new().push_opcode(OP_FALSE);
+            match status {
+                SwapStatus::Claimable => {
+                    if let (Some(preimage), Some(peer_txid), Some(peer_vout)) =
+                        (record.preimage.clone(), record.peer_htlc_txid.clone(), record.peer_vout)
+                    {
+                        match self.protocol.claim_swap(&peer_txid, peer_vout, &preimage, &record.destination_address) {
+                            Ok(txid) => {
+                                println!("Watcher auto-claimed swap {}:{} -> {}", peer_txid, peer_vout, txid);
+                                status = SwapStatus::Claimed;
+                            }
+                            Err(e) => eprintln!("Watcher failed to claim swap {}:{}: {}", peer_txid, peer_vout, e),
+                        }
+                    }
+                }
+                SwapStatus::Refundable => {
+                    match self.protocol.refund_swap(&record.htlc_txid, record.vout, &record.destination_address) {
+                        Ok(txid) => {
+                            println!("Watcher auto-refunded swap {}:{} -> {}", record.htlc_txid, record.vout, txid);
+                            status = SwapStatus::Refunded;
+                        }
+                        Err(e) => eprintln!("Watcher failed to refund swap {}:{}: {}", record.htlc_txid, record.vout, e),
+                    }
+                }
+                _ => {}
+            }
 
-let tx_in = TxIn {
-previous_output: OutPoint::new(Txid::from_str(htlc_txid)?, htlc_output.n),
-script_sig: refund_script,
-sequence: 0xFFFFFFFF,
-witness: vec![],
-};
+            results.push((record, status));
+        }
+        Ok(results)
+    }
 
-let destination_address_obj = Address::from_str(destination_address)?;
-let tx_out = TxOut {
-value: htlc_output.value.to_sat(),
-script_pubkey: destination_address_obj.script_pubkey(),
-};
+    /// Runs forever, polling every `poll_interval_secs` and reporting each
+    /// tracked swap's status after every pass.
+    fn run(&self) -> Result<(), GlyphError> {
+        loop {
+            for (record, status) in self.poll_once()? {
+                println!("swap {}:{} [{}]: {}", record.htlc_txid, record.vout, record.role.as_str(), status.as_str());
+            }
+            std::thread::sleep(std::time::Duration::from_secs(self.poll_interval_secs));
+        }
+    }
+}
 
-let tx = Transaction {
-version: 2,
-lock_time: 0,
-input: vec![tx_in],
-output: vec![tx_out],
-};
+/// Parses the shared `--multisig_threshold`/`--multisig_pubkeys` pair off an
+/// `issue`/`mint` subcommand's matches. Returns `None` when neither is set;
+/// an error if only one of the pair is present or the pubkeys don't parse.
+fn parse_multisig_config(matches: &clap::ArgMatches) -> Result<Option<MultisigConfig>, GlyphError> {
+    let threshold = matches.value_of("multisig_threshold");
+    let pubkeys = matches.value_of("multisig_pubkeys");
+
+    match (threshold, pubkeys) {
+        (None, None) => Ok(None),
+        (Some(threshold), Some(pubkeys)) => {
+            let threshold: u8 = threshold.parse()
+                .map_err(|_| GlyphError::InvalidTransaction(format!("Invalid multisig_threshold: {}", threshold)))?;
+            let signer_pubkeys = pubkeys.split(',')
+                .map(|key| PublicKey::from_str(key.trim())
+                    .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid multisig pubkey '{}': {}", key, e))))
+                .collect::<Result<Vec<PublicKey>, GlyphError>>()?;
+            let config = MultisigConfig { threshold, signer_pubkeys };
+            config.validate()?;
+            Ok(Some(config))
+        },
+        _ => Err(GlyphError::InvalidTransaction("multisig_threshold and multisig_pubkeys must be given together".to_string())),
+    }
+}
 
-let signed_tx = self.rpc_client.sign_raw_transaction_with_wallet(&tx, None, None)?;
-let txid = self.rpc_client.send_raw_transaction(&signed_tx.hex)?;
-Ok(txid.to_string())
+/// Parses a comma-separated list of hex public keys, as used for an
+/// oracle's per-digit nonce points.
+fn parse_pubkey_list(raw: &str) -> Result<Vec<PublicKey>, GlyphError> {
+    raw.split(',')
+        .map(|key| PublicKey::from_str(key.trim())
+            .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid public key '{}': {}", key, e))))
+        .collect()
 }
 
-fn get_pubkey_from_address(&self, address: &str) -> Result<PublicKey, GlyphError> {
-let address_info = self.rpc_client.get_address_info(address)?;
-PublicKey::from_str(&address_info.pubkey.ok_or_else(|| GlyphError::InvalidTransaction("No pubkey found for address".to_string()))?)
-.map_err(|e| GlyphError::InvalidTransaction(format!("Invalid pubkey for address: {}", e)))
+/// Parses a `start:end:payout_a:payout_b` DLC payout band, as used by
+/// `initiate_dlc --payout_range` (repeatable, one per band of the curve).
+fn parse_payout_range(raw: &str) -> Result<(u64, u64, u64, u64), GlyphError> {
+    let parts: Vec<&str> = raw.split(':').collect();
+    if parts.len() != 4 {
+        return Err(GlyphError::InvalidTransaction(format!("Invalid payout_range format: {}", raw)));
+    }
+    let range_start = parts[0].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid payout_range start: {}", raw)))?;
+    let range_end = parts[1].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid payout_range end: {}", raw)))?;
+    let payout_a = parts[2].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid payout_range payout_a: {}", raw)))?;
+    let payout_b = parts[3].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid payout_range payout_b: {}", raw)))?;
+    Ok((range_start, range_end, payout_a, payout_b))
 }
+
+/// Parses a `counterparty_pubkey:amount:fee` routed-swap hop, as used by
+/// `route_swap --hop` (repeatable, one per hop along the route).
+fn parse_route_hop(raw: &str) -> Result<(PublicKey, u64, u64), GlyphError> {
+    let parts: Vec<&str> = raw.split(':').collect();
+    if parts.len() != 3 {
+        return Err(GlyphError::InvalidTransaction(format!("Invalid hop format: {}", raw)));
+    }
+    let counterparty_pubkey = PublicKey::from_str(parts[0])
+        .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid hop pubkey '{}': {}", parts[0], e)))?;
+    let amount = parts[1].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid hop amount: {}", raw)))?;
+    let fee = parts[2].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid hop fee: {}", raw)))?;
+    Ok((counterparty_pubkey, amount, fee))
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -685,6 +2893,16 @@ let matches = App::new("Glyph Protocol CLI")
 .version("1.0")
 .author("Your Name")
 .about("Interacts with the Glyph Protocol on Bitcoin")
+.arg(Arg::with_name("chain_backend")
+   .long("chain_backend")
+   .takes_value(true)
+   .possible_values(&["core", "electrum"])
+   .default_value("core")
+   .help("Chain backend to use: a full bitcoind (with wallet signing) or an Electrum server (watch-only)"))
+.arg(Arg::with_name("electrum_url")
+   .long("electrum_url")
+   .takes_value(true)
+   .help("Electrum server URL, required when --chain_backend=electrum"))
 .subcommand(SubCommand::with_name("symbol")
 .about("Encode or decode a Glyph symbol")
 .arg(Arg::with_name("action")
@@ -694,6 +2912,19 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("value")
    .required(true)
    .help("The symbol or integer to encode/decode")))
+.subcommand(SubCommand::with_name("glyph_ref")
+.about("Encode or decode a checksummed glyph1... reference")
+.arg(Arg::with_name("action")
+   .required(true)
+   .possible_values(&["encode", "decode"])
+   .help("Whether to encode or decode the reference"))
+.arg(Arg::with_name("value")
+   .required(true)
+   .help("For encode: a block:tx glyph ID. For decode: a glyph1... reference"))
+.arg(Arg::with_name("holder_address")
+   .long("holder_address")
+   .takes_value(true)
+   .help("Optional holder address to bind into the reference when encoding")))
 .subcommand(SubCommand::with_name("issue")
 .about("Issue a new Glyph")
 .arg(Arg::with_name("name")
@@ -757,7 +2988,32 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("nostr_pubkey")
    .long("nostr_pubkey")
    .takes_value(true)
-   .help("Optional Nostr public key to integrate via Taproot")))
+   .help("Optional Nostr public key to integrate via Taproot"))
+.arg(Arg::with_name("psbt")
+   .long("psbt")
+   .help("Output an unsigned base64 PSBT for offline signing instead of broadcasting"))
+.arg(Arg::with_name("multisig_threshold")
+   .long("multisig_threshold")
+   .takes_value(true)
+   .help("Required signature count for multisig issuance (use with multisig_pubkeys)"))
+.arg(Arg::with_name("multisig_pubkeys")
+   .long("multisig_pubkeys")
+   .takes_value(true)
+   .help("Comma-separated signer public keys for multisig issuance"))
+.arg(Arg::with_name("multisig_taproot")
+   .long("multisig_taproot")
+   .help("Commit the multisig policy as a taproot script-path leaf instead of classic P2WSH"))
+.arg(Arg::with_name("signer")
+   .long("signer")
+   .takes_value(true)
+   .possible_values(&["core", "ledger"])
+   .default_value("core")
+   .help("Signer for this transaction: the connected Core wallet, or a Ledger device so the premine key never leaves it"))
+.arg(Arg::with_name("ledger_derivation_path")
+   .long("ledger_derivation_path")
+   .takes_value(true)
+   .default_value("m/84'/0'/0'/0/0")
+   .help("BIP32 derivation path to sign with, only used when --signer=ledger")))
 .subcommand(SubCommand::with_name("mint")
 .about("Mint new units of a Glyph")
 .arg(Arg::with_name("glyph_id")
@@ -784,24 +3040,52 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("nostr_pubkey")
    .long("nostr_pubkey")
    .takes_value(true)
-   .help("Optional Nostr public key to integrate via Taproot")))
+   .help("Optional Nostr public key to integrate via Taproot"))
+.arg(Arg::with_name("psbt")
+   .long("psbt")
+   .help("Output an unsigned base64 PSBT for offline signing instead of broadcasting"))
+.arg(Arg::with_name("multisig_threshold")
+   .long("multisig_threshold")
+   .takes_value(true)
+   .help("Required signature count for multisig issuance (use with multisig_pubkeys)"))
+.arg(Arg::with_name("multisig_pubkeys")
+   .long("multisig_pubkeys")
+   .takes_value(true)
+   .help("Comma-separated signer public keys for multisig issuance"))
+.arg(Arg::with_name("multisig_taproot")
+   .long("multisig_taproot")
+   .help("Commit the multisig policy as a taproot script-path leaf instead of classic P2WSH"))
+.arg(Arg::with_name("signer")
+   .long("signer")
+   .takes_value(true)
+   .possible_values(&["core", "ledger"])
+   .default_value("core")
+   .help("Signer for this transaction: the connected Core wallet, or a Ledger device so the mint key never leaves it"))
+.arg(Arg::with_name("ledger_derivation_path")
+   .long("ledger_derivation_path")
+   .takes_value(true)
+   .default_value("m/84'/0'/0'/0/0")
+   .help("BIP32 derivation path to sign with, only used when --signer=ledger")))
 .subcommand(SubCommand::with_name("transfer")
-.about("Transfer Glyphs")
-.arg(Arg::with_name("glyph_id")
-   .required(true)
-   .help("Glyph ID to transfer in BLOCK:TX format"))
+.about("Transfer Glyphs via one or more edicts")
 .arg(Arg::with_name("input_txid")
    .required(true)
    .help("Transaction ID of the input UTXO"))
 .arg(Arg::with_name("input_vout")
    .required(true)
    .help("Output index of the input UTXO"))
-.arg(Arg::with_name("amount")
+.arg(Arg::with_name("edict")
+   .long("edict")
+   .takes_value(true)
+   .multiple(true)
    .required(true)
-   .help("Amount of Glyphs to transfer"))
+   .help("An edict as glyph_id:amount:output_index, e.g. 840000:3:5:1 (repeatable)"))
 .arg(Arg::with_name("destination_address")
+   .long("destination_address")
+   .takes_value(true)
+   .multiple(true)
    .required(true)
-   .help("Destination address for the Glyphs"))
+   .help("Destination address for output index i+1 (repeatable, in output order)"))
 .arg(Arg::with_name("change_address")
    .long("change_address")
    .takes_value(true)
@@ -817,7 +3101,21 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("nostr_pubkey")
    .long("nostr_pubkey")
    .takes_value(true)
-   .help("Optional Nostr public key to integrate via Taproot")))
+   .help("Optional Nostr public key to integrate via Taproot"))
+.arg(Arg::with_name("psbt")
+   .long("psbt")
+   .help("Output an unsigned base64 PSBT for offline signing instead of broadcasting"))
+.arg(Arg::with_name("signer")
+   .long("signer")
+   .takes_value(true)
+   .possible_values(&["core", "ledger"])
+   .default_value("core")
+   .help("Signer for this transaction: the connected Core wallet, or a Ledger device so the transfer key never leaves it"))
+.arg(Arg::with_name("ledger_derivation_path")
+   .long("ledger_derivation_path")
+   .takes_value(true)
+   .default_value("m/84'/0'/0'/0/0")
+   .help("BIP32 derivation path to sign with, only used when --signer=ledger")))
 .subcommand(SubCommand::with_name("initiate_swap")
 .about("Initiate an atomic swap")
 .arg(Arg::with_name("glyph_id")
@@ -832,9 +3130,6 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("counterparty_pubkey")
    .required(true)
    .help("Counterparty's public key"))
-.arg(Arg::with_name("secret")
-   .required(true)
-   .help("Secret for the HTLC"))
 .arg(Arg::with_name("timelock")
    .required(true)
    .help("Timelock for the HTLC")))
@@ -858,28 +3153,265 @@ let matches = App::new("Glyph Protocol CLI")
 .arg(Arg::with_name("timelock")
    .required(true)
    .help("Timelock for the HTLC")))
-   .subcommand(SubCommand::with_name("claim_glyph")
-   .about("Claim Glyphs from an HTLC")
+   .subcommand(SubCommand::with_name("claim_swap")
+   .about("Claim Glyphs from the counterparty's matching HTLC by revealing the preimage")
    .arg(Arg::with_name("htlc_txid")
        .required(true)
-       .help("Transaction ID of the HTLC"))
-   .arg(Arg::with_name("secret")
+       .help("Transaction ID of the counterparty's HTLC (linked via link_swap), not your own"))
+   .arg(Arg::with_name("vout")
        .required(true)
-       .help("Secret to claim the HTLC"))
+       .help("Output index of the counterparty's HTLC"))
+   .arg(Arg::with_name("preimage")
+       .required(true)
+       .help("Hex-encoded preimage that hashes to the HTLC's secret_hash"))
    .arg(Arg::with_name("destination_address")
        .required(true)
-       .help("Destination address for the claimed Glyphs")))
-.subcommand(SubCommand::with_name("refund_glyph")
+       .help("Destination address for the claimed Glyphs"))
+   .arg(Arg::with_name("signer")
+       .long("signer")
+       .takes_value(true)
+       .possible_values(&["core", "ledger"])
+       .default_value("core")
+       .help("Signer for this claim: the connected Core wallet, or a Ledger device so the key never leaves it"))
+   .arg(Arg::with_name("ledger_derivation_path")
+       .long("ledger_derivation_path")
+       .takes_value(true)
+       .default_value("m/84'/0'/0'/0/0")
+       .help("BIP32 derivation path to sign with, only used when --signer=ledger")))
+.subcommand(SubCommand::with_name("refund_swap")
    .about("Refund Glyphs from an expired HTLC")
    .arg(Arg::with_name("htlc_txid")
        .required(true)
        .help("Transaction ID of the HTLC"))
+   .arg(Arg::with_name("vout")
+       .required(true)
+       .help("Output index of the HTLC"))
+   .arg(Arg::with_name("destination_address")
+       .required(true)
+       .help("Destination address for the refunded Glyphs"))
+   .arg(Arg::with_name("signer")
+       .long("signer")
+       .takes_value(true)
+       .possible_values(&["core", "ledger"])
+       .default_value("core")
+       .help("Signer for this refund: the connected Core wallet, or a Ledger device so the key never leaves it"))
+   .arg(Arg::with_name("ledger_derivation_path")
+       .long("ledger_derivation_path")
+       .takes_value(true)
+       .default_value("m/84'/0'/0'/0/0")
+       .help("BIP32 derivation path to sign with, only used when --signer=ledger")))
+.subcommand(SubCommand::with_name("route_swap")
+   .about("Route a Glyph transfer through one or more intermediary makers (a multi-hop CoinSwap)")
+   .arg(Arg::with_name("glyph_id")
+       .required(true)
+       .help("Glyph ID to swap in BLOCK:TX format"))
+   .arg(Arg::with_name("hop")
+       .long("hop")
+       .takes_value(true)
+       .multiple(true)
+       .required(true)
+       .help("A hop as counterparty_pubkey:amount:fee, ordered from the first intermediary to the final receiver (repeatable)"))
+   .arg(Arg::with_name("base_timelock")
+       .long("base_timelock")
+       .takes_value(true)
+       .required(true)
+       .help("Timelock for the first hop"))
+   .arg(Arg::with_name("timelock_step")
+       .long("timelock_step")
+       .takes_value(true)
+       .required(true)
+       .help("Amount the timelock shortens by for each hop further down the route")))
+.subcommand(SubCommand::with_name("link_swap")
+   .about("Record the counterparty's HTLC for a tracked swap so it can be claimed and the watcher can find it")
+   .arg(Arg::with_name("htlc_txid")
+       .required(true)
+       .help("Transaction ID of your own tracked HTLC"))
+   .arg(Arg::with_name("vout")
+       .required(true)
+       .help("Output index of your own tracked HTLC"))
+   .arg(Arg::with_name("peer_htlc_txid")
+       .required(true)
+       .help("Transaction ID of the counterparty's matching HTLC"))
+   .arg(Arg::with_name("peer_vout")
+       .required(true)
+       .help("Output index of the counterparty's matching HTLC"))
+   .arg(Arg::with_name("peer_timelock")
+       .required(true)
+       .help("Timelock the counterparty locked their matching HTLC with")))
+.subcommand(SubCommand::with_name("watch")
+   .about("Run a watchtower that auto-claims and auto-refunds tracked swaps")
+   .arg(Arg::with_name("poll_interval_secs")
+       .long("poll_interval_secs")
+       .takes_value(true)
+       .help("Seconds to sleep between chain polls (default 30)")))
+.subcommand(SubCommand::with_name("finalize")
+   .about("Finalize and broadcast a signed PSBT")
+   .arg(Arg::with_name("psbt")
+       .required(true)
+       .help("Base64-encoded PSBT, fully signed by all required parties")))
+.subcommand(SubCommand::with_name("combine_multisig")
+   .about("Combine partially-signed multisig PSBTs and broadcast once threshold is met")
+   .arg(Arg::with_name("psbt")
+       .long("psbt")
+       .takes_value(true)
+       .multiple(true)
+       .required(true)
+       .help("A signer's partially-signed PSBT (repeatable, one per signer)")))
+.subcommand(SubCommand::with_name("initiate_dlc")
+   .about("Lock Glyphs into an oracle-settled Discreet Log Contract")
+   .arg(Arg::with_name("glyph_id")
+       .required(true)
+       .help("Glyph ID to lock in BLOCK:TX format"))
+   .arg(Arg::with_name("amount")
+       .required(true)
+       .help("Amount of Glyphs to lock"))
+   .arg(Arg::with_name("own_pubkey")
+       .required(true)
+       .help("This party's public key"))
+   .arg(Arg::with_name("counterparty_pubkey")
+       .required(true)
+       .help("Counterparty's public key"))
+   .arg(Arg::with_name("oracle_pubkey")
+       .long("oracle_pubkey")
+       .takes_value(true)
+       .required(true)
+       .help("The oracle's public key"))
+   .arg(Arg::with_name("oracle_nonce_points")
+       .long("oracle_nonce_points")
+       .takes_value(true)
+       .required(true)
+       .help("Comma-separated oracle nonce points, one per digit, most significant first"))
+   .arg(Arg::with_name("payout_range")
+       .long("payout_range")
+       .takes_value(true)
+       .multiple(true)
+       .required(true)
+       .help("A payout band as start:end:payout_a:payout_b over the outcome's digit value (repeatable)"))
+   .arg(Arg::with_name("timelock")
+       .required(true)
+       .help("Refund timelock if the oracle never attests"))
+   .arg(Arg::with_name("change_address")
+       .long("change_address")
+       .takes_value(true)
+       .help("Change address for Bitcoin")))
+.subcommand(SubCommand::with_name("execute_cet")
+   .about("Complete and broadcast a CET once the oracle has attested")
+   .arg(Arg::with_name("funding_txid")
+       .required(true)
+       .help("Transaction ID of the DLC funding output"))
+   .arg(Arg::with_name("vout")
+       .required(true)
+       .help("Output index of the DLC funding output"))
+   .arg(Arg::with_name("own_pubkey")
+       .required(true)
+       .help("This party's public key"))
+   .arg(Arg::with_name("counterparty_pubkey")
+       .required(true)
+       .help("Counterparty's public key"))
+   .arg(Arg::with_name("digit_prefix")
+       .required(true)
+       .help("The attested outcome's digit prefix this CET covers, e.g. 101"))
+   .arg(Arg::with_name("oracle_pubkey")
+       .long("oracle_pubkey")
+       .takes_value(true)
+       .required(true)
+       .help("The oracle's public key"))
+   .arg(Arg::with_name("oracle_nonce_points")
+       .long("oracle_nonce_points")
+       .takes_value(true)
+       .required(true)
+       .help("Comma-separated oracle nonce points, one per digit, most significant first"))
+   .arg(Arg::with_name("payout_range")
+       .long("payout_range")
+       .takes_value(true)
+       .multiple(true)
+       .required(true)
+       .help("A payout band as start:end:payout_a:payout_b, the same set given to initiate_dlc"))
+   .arg(Arg::with_name("timelock")
+       .required(true)
+       .help("The funding output's refund timelock, the same one given to initiate_dlc"))
+   .arg(Arg::with_name("counterparty_adaptor_sig")
+       .long("counterparty_adaptor_sig")
+       .takes_value(true)
+       .required(true)
+       .help("The (r, s') pair presign_cet produced for this CET, as 64 bytes hex"))
+   .arg(Arg::with_name("adaptor_secret")
+       .long("adaptor_secret")
+       .takes_value(true)
+       .required(true)
+       .help("The oracle attestation's revealed discrete log for digit_prefix, as hex"))
+   .arg(Arg::with_name("destination_address")
+       .required(true)
+       .help("Destination address for the payout")))
+.subcommand(SubCommand::with_name("presign_cet")
+   .about("Produce an adaptor signature for a CET, for the counterparty to later complete")
+   .arg(Arg::with_name("funding_txid")
+       .required(true)
+       .help("Transaction ID of the DLC funding output"))
+   .arg(Arg::with_name("vout")
+       .required(true)
+       .help("Output index of the DLC funding output"))
+   .arg(Arg::with_name("own_pubkey")
+       .required(true)
+       .help("This party's public key"))
+   .arg(Arg::with_name("counterparty_pubkey")
+       .required(true)
+       .help("Counterparty's public key"))
+   .arg(Arg::with_name("digit_prefix")
+       .required(true)
+       .help("The outcome digit prefix this CET covers, e.g. 101"))
+   .arg(Arg::with_name("oracle_pubkey")
+       .long("oracle_pubkey")
+       .takes_value(true)
+       .required(true)
+       .help("The oracle's public key"))
+   .arg(Arg::with_name("oracle_nonce_points")
+       .long("oracle_nonce_points")
+       .takes_value(true)
+       .required(true)
+       .help("Comma-separated oracle nonce points, one per digit, most significant first"))
+   .arg(Arg::with_name("payout_range")
+       .long("payout_range")
+       .takes_value(true)
+       .multiple(true)
+       .required(true)
+       .help("A payout band as start:end:payout_a:payout_b, the same set given to initiate_dlc"))
+   .arg(Arg::with_name("timelock")
+       .required(true)
+       .help("The funding output's refund timelock, the same one given to initiate_dlc"))
+   .arg(Arg::with_name("destination_address")
+       .required(true)
+       .help("Destination address for the payout, the same one execute_cet will be given")))
+.subcommand(SubCommand::with_name("refund_dlc")
+   .about("Refund a DLC's funding output once its timelock has passed unattested")
+   .arg(Arg::with_name("funding_txid")
+       .required(true)
+       .help("Transaction ID of the DLC funding output"))
+   .arg(Arg::with_name("vout")
+       .required(true)
+       .help("Output index of the DLC funding output"))
+   .arg(Arg::with_name("own_pubkey")
+       .required(true)
+       .help("This party's public key"))
+   .arg(Arg::with_name("counterparty_pubkey")
+       .required(true)
+       .help("Counterparty's public key"))
+   .arg(Arg::with_name("timelock")
+       .required(true)
+       .help("The funding output's refund timelock"))
    .arg(Arg::with_name("destination_address")
        .required(true)
        .help("Destination address for the refunded Glyphs")))
 .get_matches();
 
-let glyph_protocol = GlyphProtocol::new(Network::Testnet, "http://localhost:18332", "rpcuser", "rpcpassword")?;
+let mut glyph_protocol = match matches.value_of("chain_backend").unwrap() {
+    "electrum" => {
+        let electrum_url = matches.value_of("electrum_url")
+            .ok_or_else(|| GlyphError::InvalidTransaction("--electrum_url is required when --chain_backend=electrum".to_string()))?;
+        GlyphProtocol::new_electrum(Network::Testnet, electrum_url)?
+    },
+    _ => GlyphProtocol::new(Network::Testnet, "http://localhost:18332", "rpcuser", "rpcpassword")?,
+};
 
 match matches.subcommand() {
 ("symbol", Some(symbol_matches)) => {
@@ -902,6 +3434,32 @@ match matches.subcommand() {
        _ => unreachable!(),
    }
 },
+("glyph_ref", Some(ref_matches)) => {
+   let action = ref_matches.value_of("action").unwrap();
+   let value = ref_matches.value_of("value").unwrap();
+   match action {
+       "encode" => {
+           let (block_height, tx_index) = glyph_protocol.parse_glyph_id(value)?;
+           let holder_address = ref_matches.value_of("holder_address");
+           match glyph_protocol.encode_glyph_ref(block_height, tx_index, holder_address) {
+               Ok(encoded) => println!("Glyph reference: {}", encoded),
+               Err(e) => eprintln!("Error: {}", e),
+           }
+       },
+       "decode" => {
+           match glyph_protocol.decode_glyph_ref(value) {
+               Ok((block_height, tx_index, holder_address)) => {
+                   println!("Glyph ID: {}:{}", block_height, tx_index);
+                   if let Some(address) = holder_address {
+                       println!("Holder address: {}", address);
+                   }
+               },
+               Err(e) => eprintln!("Error: {}", e),
+           }
+       },
+       _ => unreachable!(),
+   }
+},
 ("issue", Some(issue_matches)) => {
    let name = issue_matches.value_of("name").unwrap();
    let divisibility = issue_matches.value_of("divisibility").unwrap().parse()?;
@@ -918,10 +3476,20 @@ match matches.subcommand() {
    let fee_per_byte = issue_matches.value_of("fee").unwrap().parse()?;
    let live = issue_matches.is_present("live");
    let nostr_pubkey = issue_matches.value_of("nostr_pubkey");
+   let psbt = issue_matches.is_present("psbt");
+   let multisig = parse_multisig_config(issue_matches)?;
+   let multisig_taproot = issue_matches.is_present("multisig_taproot");
+
+   if issue_matches.value_of("signer") == Some("ledger") {
+       glyph_protocol.use_ledger_signer("http://localhost:18332", "rpcuser", "rpcpassword",
+                                        issue_matches.value_of("ledger_derivation_path").unwrap())?;
+   }
 
    match glyph_protocol.etch_glyph(name, divisibility, symbol, premine, mint_cap, mint_amount,
                                    start_height, end_height, start_offset, end_offset,
-                                   destination_address, change_address, fee_per_byte, live, nostr_pubkey) {
+                                   destination_address, change_address, fee_per_byte, live, nostr_pubkey, psbt,
+                                   multisig.as_ref(), multisig_taproot) {
+       Ok(result) if psbt => println!("Unsigned PSBT:\n{}", result),
        Ok(txid) => println!("Glyph issued successfully. Transaction ID: {}", txid),
        Err(e) => eprintln!("Error: {}", e),
    }
@@ -934,24 +3502,56 @@ match matches.subcommand() {
    let fee_per_byte = mint_matches.value_of("fee").unwrap().parse()?;
    let live = mint_matches.is_present("live");
    let nostr_pubkey = mint_matches.value_of("nostr_pubkey");
+   let psbt = mint_matches.is_present("psbt");
+   let multisig = parse_multisig_config(mint_matches)?;
+   let multisig_taproot = mint_matches.is_present("multisig_taproot");
+
+   if mint_matches.value_of("signer") == Some("ledger") {
+       glyph_protocol.use_ledger_signer("http://localhost:18332", "rpcuser", "rpcpassword",
+                                        mint_matches.value_of("ledger_derivation_path").unwrap())?;
+   }
 
-   match glyph_protocol.mint_glyph(glyph_id, amount, destination_address, change_address, fee_per_byte, live, nostr_pubkey) {
+   match glyph_protocol.mint_glyph(glyph_id, amount, destination_address, change_address, fee_per_byte, live, nostr_pubkey, psbt,
+                                    multisig.as_ref(), multisig_taproot) {
+       Ok(result) if psbt => println!("Unsigned PSBT:\n{}", result),
        Ok(txid) => println!("Glyphs minted successfully. Transaction ID: {}", txid),
        Err(e) => eprintln!("Error: {}", e),
    }
 },
 ("transfer", Some(transfer_matches)) => {
-   let glyph_id = transfer_matches.value_of("glyph_id").unwrap();
    let input_txid = transfer_matches.value_of("input_txid").unwrap();
    let input_vout = transfer_matches.value_of("input_vout").unwrap().parse()?;
-   let amount = transfer_matches.value_of("amount").unwrap().parse()?;
-   let destination_address = transfer_matches.value_of("destination_address").unwrap();
    let change_address = transfer_matches.value_of("change_address");
    let fee_per_byte = transfer_matches.value_of("fee").unwrap().parse()?;
    let live = transfer_matches.is_present("live");
    let nostr_pubkey = transfer_matches.value_of("nostr_pubkey");
+   let psbt = transfer_matches.is_present("psbt");
+
+   let edicts: Vec<Edict> = transfer_matches.values_of("edict").unwrap()
+       .map(|raw| {
+           // Split from the right so a BLOCK:TX glyph id keeps its own ':'.
+           let parts: Vec<&str> = raw.rsplitn(3, ':').collect();
+           if parts.len() != 3 {
+               return Err(GlyphError::InvalidTransaction(format!("Invalid edict format: {}", raw)));
+           }
+           let (block_height, tx_index) = glyph_protocol.parse_glyph_id(parts[2])?;
+           let amount = parts[1].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid edict amount: {}", raw)))?;
+           let output_index = parts[0].parse().map_err(|_| GlyphError::InvalidTransaction(format!("Invalid edict output index: {}", raw)))?;
+           Ok(Edict { glyph_id: (block_height, tx_index), amount, output_index })
+       })
+       .collect::<Result<Vec<Edict>, GlyphError>>()?;
+
+   let destination_addresses: Vec<String> = transfer_matches.values_of("destination_address").unwrap()
+       .map(|s| s.to_string())
+       .collect();
+
+   if transfer_matches.value_of("signer") == Some("ledger") {
+       glyph_protocol.use_ledger_signer("http://localhost:18332", "rpcuser", "rpcpassword",
+                                        transfer_matches.value_of("ledger_derivation_path").unwrap())?;
+   }
 
-   match glyph_protocol.transfer_glyph(glyph_id, input_txid, input_vout, amount, destination_address, change_address, fee_per_byte, live, nostr_pubkey) {
+   match glyph_protocol.transfer_glyph(input_txid, input_vout, &edicts, &destination_addresses, change_address, fee_per_byte, live, nostr_pubkey, psbt) {
+       Ok(result) if psbt => println!("Unsigned PSBT:\n{}", result),
        Ok(txid) => println!("Glyphs transferred successfully. Transaction ID: {}", txid),
        Err(e) => eprintln!("Error: {}", e),
    }
@@ -961,18 +3561,18 @@ match matches.subcommand() {
    let amount = initiate_matches.value_of("amount").unwrap().parse()?;
    let destination_address = initiate_matches.value_of("destination_address").unwrap();
    let counterparty_pubkey = initiate_matches.value_of("counterparty_pubkey").unwrap();
-   let secret = initiate_matches.value_of("secret").unwrap();
    let timelock = initiate_matches.value_of("timelock").unwrap().parse()?;
 
-   match glyph_protocol.initiate_swap(glyph_id, amount, destination_address, counterparty_pubkey, secret, timelock) {
-       Ok(txid) => {
+   match glyph_protocol.initiate_swap(glyph_id, amount, destination_address, counterparty_pubkey, timelock) {
+       Ok((txid, preimage)) => {
            println!("Swap initiated successfully. Transaction ID: {}", txid);
            println!("Provide the following details to your counterparty:");
            println!("Glyph ID: {}", glyph_id);
            println!("Amount: {}", amount);
-           println!("Secret Hash: {}", hex::encode(sha256::Hash::hash(secret.as_bytes())));
+           println!("Secret Hash: {}", hex::encode(hash160::Hash::hash(&preimage)));
            println!("Timelock: {}", timelock);
            println!("Your Public Key: {}", glyph_protocol.get_pubkey_from_address(destination_address)?);
+           println!("Keep this preimage secret until you claim the other leg: {}", hex::encode(preimage));
        },
        Err(e) => eprintln!("Error: {}", e),
    }
@@ -995,27 +3595,478 @@ match matches.subcommand() {
        Err(e) => eprintln!("Error: {}", e),
    }
 },
-("claim_glyph", Some(claim_matches)) => {
+("claim_swap", Some(claim_matches)) => {
    let htlc_txid = claim_matches.value_of("htlc_txid").unwrap();
-   let secret = claim_matches.value_of("secret").unwrap();
+   let vout: u32 = claim_matches.value_of("vout").unwrap().parse()?;
+   let preimage = hex::decode(claim_matches.value_of("preimage").unwrap())?;
    let destination_address = claim_matches.value_of("destination_address").unwrap();
 
-   match glyph_protocol.claim_glyph(htlc_txid, secret, destination_address) {
+   if claim_matches.value_of("signer") == Some("ledger") {
+       glyph_protocol.use_ledger_signer("http://localhost:18332", "rpcuser", "rpcpassword",
+                                        claim_matches.value_of("ledger_derivation_path").unwrap())?;
+   }
+
+   match glyph_protocol.claim_swap(htlc_txid, vout, &preimage, destination_address) {
        Ok(txid) => println!("Glyphs claimed successfully. Transaction ID: {}", txid),
        Err(e) => eprintln!("Error: {}", e),
    }
 },
-("refund_glyph", Some(refund_matches)) => {
+("refund_swap", Some(refund_matches)) => {
    let htlc_txid = refund_matches.value_of("htlc_txid").unwrap();
+   let vout: u32 = refund_matches.value_of("vout").unwrap().parse()?;
    let destination_address = refund_matches.value_of("destination_address").unwrap();
 
-   match glyph_protocol.refund_glyph(htlc_txid, destination_address) {
+   if refund_matches.value_of("signer") == Some("ledger") {
+       glyph_protocol.use_ledger_signer("http://localhost:18332", "rpcuser", "rpcpassword",
+                                        refund_matches.value_of("ledger_derivation_path").unwrap())?;
+   }
+
+   match glyph_protocol.refund_swap(htlc_txid, vout, destination_address) {
        Ok(txid) => println!("Glyphs refunded successfully. Transaction ID: {}", txid),
        Err(e) => eprintln!("Error: {}", e),
    }
 },
+("route_swap", Some(route_matches)) => {
+   let glyph_id = route_matches.value_of("glyph_id").unwrap();
+   let hops: Vec<(PublicKey, u64, u64)> = route_matches.values_of("hop").unwrap()
+       .map(parse_route_hop)
+       .collect::<Result<Vec<_>, _>>()?;
+   let base_timelock = route_matches.value_of("base_timelock").unwrap().parse()?;
+   let timelock_step = route_matches.value_of("timelock_step").unwrap().parse()?;
+
+   match glyph_protocol.route_swap(glyph_id, &hops, base_timelock, timelock_step) {
+       Ok(broadcast_hops) => {
+           println!("Route broadcast across {} hop(s):", broadcast_hops.len());
+           for (hop_index, (txid, timelock)) in broadcast_hops.iter().enumerate() {
+               println!("Hop {}: Transaction ID {} (timelock {})", hop_index, txid, timelock);
+           }
+       },
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("link_swap", Some(link_matches)) => {
+   let htlc_txid = link_matches.value_of("htlc_txid").unwrap();
+   let vout: u32 = link_matches.value_of("vout").unwrap().parse()?;
+   let peer_htlc_txid = link_matches.value_of("peer_htlc_txid").unwrap();
+   let peer_vout: u32 = link_matches.value_of("peer_vout").unwrap().parse()?;
+   let peer_timelock: u32 = link_matches.value_of("peer_timelock").unwrap().parse()?;
+
+   match glyph_protocol.link_swap(htlc_txid, vout, peer_htlc_txid, peer_vout, peer_timelock) {
+       Ok(()) => println!("Linked swap {}:{} to peer HTLC {}:{}", htlc_txid, vout, peer_htlc_txid, peer_vout),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("watch", Some(watch_matches)) => {
+   let poll_interval_secs: u64 = watch_matches.value_of("poll_interval_secs").unwrap_or("30").parse()?;
+   let watcher = SwapWatcher::new(&glyph_protocol, poll_interval_secs);
+
+   if let Err(e) = watcher.run() {
+       eprintln!("Watcher error: {}", e);
+   }
+},
+("finalize", Some(finalize_matches)) => {
+   let psbt_base64 = finalize_matches.value_of("psbt").unwrap();
+
+   match glyph_protocol.finalize_and_broadcast(psbt_base64) {
+       Ok(txid) => println!("PSBT finalized and broadcast. Transaction ID: {}", txid),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("combine_multisig", Some(combine_matches)) => {
+   let partial_psbts: Vec<String> = combine_matches.values_of("psbt").unwrap()
+       .map(|s| s.to_string())
+       .collect();
+
+   match glyph_protocol.combine_and_finalize_multisig(&partial_psbts) {
+       Ok(txid) => println!("Multisig PSBT combined, finalized, and broadcast. Transaction ID: {}", txid),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("initiate_dlc", Some(dlc_matches)) => {
+   let glyph_id = dlc_matches.value_of("glyph_id").unwrap();
+   let amount = dlc_matches.value_of("amount").unwrap().parse()?;
+   let own_pubkey = PublicKey::from_str(dlc_matches.value_of("own_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid own_pubkey: {}", e)))?;
+   let counterparty_pubkey = PublicKey::from_str(dlc_matches.value_of("counterparty_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty_pubkey: {}", e)))?;
+   let oracle_pubkey = PublicKey::from_str(dlc_matches.value_of("oracle_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid oracle_pubkey: {}", e)))?;
+   let nonce_points = parse_pubkey_list(dlc_matches.value_of("oracle_nonce_points").unwrap())?;
+   let num_digits = nonce_points.len() as u32;
+   let announcement = OracleAnnouncement { oracle_pubkey, nonce_points };
+   let payout_ranges: Vec<(u64, u64, u64, u64)> = dlc_matches.values_of("payout_range").unwrap()
+       .map(parse_payout_range)
+       .collect::<Result<Vec<(u64, u64, u64, u64)>, GlyphError>>()?;
+   let timelock = dlc_matches.value_of("timelock").unwrap().parse()?;
+   let change_address = dlc_matches.value_of("change_address");
+
+   match glyph_protocol.initiate_dlc(glyph_id, amount, &own_pubkey, &counterparty_pubkey, &announcement, &payout_ranges, num_digits, timelock, change_address) {
+       Ok((txid, cets)) => {
+           println!("DLC funding transaction broadcast. Transaction ID: {}", txid);
+           for cet in &cets {
+               println!("CET prefix {:?}: payout_a={} payout_b={}", cet.digit_prefix, cet.payout_a, cet.payout_b);
+           }
+       },
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("presign_cet", Some(presign_matches)) => {
+   let funding_txid = presign_matches.value_of("funding_txid").unwrap();
+   let vout: u32 = presign_matches.value_of("vout").unwrap().parse()?;
+   let own_pubkey = PublicKey::from_str(presign_matches.value_of("own_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid own_pubkey: {}", e)))?;
+   let counterparty_pubkey = PublicKey::from_str(presign_matches.value_of("counterparty_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty_pubkey: {}", e)))?;
+   let digit_prefix: Vec<u8> = presign_matches.value_of("digit_prefix").unwrap()
+       .chars()
+       .map(|c| c.to_digit(2).map(|d| d as u8).ok_or_else(|| GlyphError::InvalidTransaction(format!("Invalid digit in digit_prefix: {}", c))))
+       .collect::<Result<Vec<u8>, GlyphError>>()?;
+   let oracle_pubkey = PublicKey::from_str(presign_matches.value_of("oracle_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid oracle_pubkey: {}", e)))?;
+   let nonce_points = parse_pubkey_list(presign_matches.value_of("oracle_nonce_points").unwrap())?;
+   let num_digits = nonce_points.len() as u32;
+   let announcement = OracleAnnouncement { oracle_pubkey, nonce_points };
+   let payout_ranges: Vec<(u64, u64, u64, u64)> = presign_matches.values_of("payout_range").unwrap()
+       .map(parse_payout_range)
+       .collect::<Result<Vec<(u64, u64, u64, u64)>, GlyphError>>()?;
+   let timelock: u32 = presign_matches.value_of("timelock").unwrap().parse()?;
+   let destination_address = presign_matches.value_of("destination_address").unwrap();
+
+   let cets = glyph_protocol.build_dlc_cets(&announcement, &payout_ranges, num_digits)?;
+   let cet_index = cets.iter().position(|cet| cet.digit_prefix == digit_prefix)
+       .ok_or_else(|| GlyphError::InvalidTransaction(format!("No CET covers digit_prefix {:?}", digit_prefix)))?;
+
+   match glyph_protocol.presign_cet(funding_txid, vout, &own_pubkey, &counterparty_pubkey, &cets, cet_index, timelock, destination_address) {
+       Ok((r, s_prime)) => println!("Adaptor signature: {}{}", hex::encode(r), hex::encode(s_prime)),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("execute_cet", Some(cet_matches)) => {
+   let funding_txid = cet_matches.value_of("funding_txid").unwrap();
+   let vout: u32 = cet_matches.value_of("vout").unwrap().parse()?;
+   let own_pubkey = PublicKey::from_str(cet_matches.value_of("own_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid own_pubkey: {}", e)))?;
+   let counterparty_pubkey = PublicKey::from_str(cet_matches.value_of("counterparty_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty_pubkey: {}", e)))?;
+   let digit_prefix: Vec<u8> = cet_matches.value_of("digit_prefix").unwrap()
+       .chars()
+       .map(|c| c.to_digit(2).map(|d| d as u8).ok_or_else(|| GlyphError::InvalidTransaction(format!("Invalid digit in digit_prefix: {}", c))))
+       .collect::<Result<Vec<u8>, GlyphError>>()?;
+   let oracle_pubkey = PublicKey::from_str(cet_matches.value_of("oracle_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid oracle_pubkey: {}", e)))?;
+   let nonce_points = parse_pubkey_list(cet_matches.value_of("oracle_nonce_points").unwrap())?;
+   let num_digits = nonce_points.len() as u32;
+   let announcement = OracleAnnouncement { oracle_pubkey, nonce_points };
+   let payout_ranges: Vec<(u64, u64, u64, u64)> = cet_matches.values_of("payout_range").unwrap()
+       .map(parse_payout_range)
+       .collect::<Result<Vec<(u64, u64, u64, u64)>, GlyphError>>()?;
+   let timelock: u32 = cet_matches.value_of("timelock").unwrap().parse()?;
+   let counterparty_adaptor_sig_bytes = hex::decode(cet_matches.value_of("counterparty_adaptor_sig").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty_adaptor_sig hex: {}", e)))?;
+   let r_bytes: [u8; 32] = counterparty_adaptor_sig_bytes.get(..32)
+       .ok_or_else(|| GlyphError::InvalidTransaction("counterparty_adaptor_sig must be 64 bytes".to_string()))?
+       .try_into().unwrap();
+   let s_prime_bytes: [u8; 32] = counterparty_adaptor_sig_bytes.get(32..64)
+       .ok_or_else(|| GlyphError::InvalidTransaction("counterparty_adaptor_sig must be 64 bytes".to_string()))?
+       .try_into().unwrap();
+   let counterparty_adaptor_sig: ([u8; 32], [u8; 32]) = (r_bytes, s_prime_bytes);
+   let adaptor_secret_bytes = hex::decode(cet_matches.value_of("adaptor_secret").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid adaptor_secret hex: {}", e)))?;
+   let adaptor_secret: [u8; 32] = adaptor_secret_bytes.try_into()
+       .map_err(|_| GlyphError::InvalidTransaction("adaptor_secret must be 32 bytes".to_string()))?;
+   let destination_address = cet_matches.value_of("destination_address").unwrap();
+
+   let cets = glyph_protocol.build_dlc_cets(&announcement, &payout_ranges, num_digits)?;
+   let cet_index = cets.iter().position(|cet| cet.digit_prefix == digit_prefix)
+       .ok_or_else(|| GlyphError::InvalidTransaction(format!("No CET covers digit_prefix {:?}", digit_prefix)))?;
+
+   match glyph_protocol.execute_dlc_cet(funding_txid, vout, &own_pubkey, &counterparty_pubkey, &cets, cet_index, timelock, &counterparty_adaptor_sig, &adaptor_secret, destination_address) {
+       Ok(txid) => println!("CET broadcast. Transaction ID: {}", txid),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
+("refund_dlc", Some(refund_matches)) => {
+   let funding_txid = refund_matches.value_of("funding_txid").unwrap();
+   let vout: u32 = refund_matches.value_of("vout").unwrap().parse()?;
+   let own_pubkey = PublicKey::from_str(refund_matches.value_of("own_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid own_pubkey: {}", e)))?;
+   let counterparty_pubkey = PublicKey::from_str(refund_matches.value_of("counterparty_pubkey").unwrap())
+       .map_err(|e| GlyphError::InvalidTransaction(format!("Invalid counterparty_pubkey: {}", e)))?;
+   let timelock: u32 = refund_matches.value_of("timelock").unwrap().parse()?;
+   let destination_address = refund_matches.value_of("destination_address").unwrap();
+
+   match glyph_protocol.refund_dlc(funding_txid, vout, &own_pubkey, &counterparty_pubkey, timelock, destination_address) {
+       Ok(txid) => println!("DLC refunded successfully. Transaction ID: {}", txid),
+       Err(e) => eprintln!("Error: {}", e),
+   }
+},
 _ => println!("Invalid command. Use --help for usage information."),
 }
 
 Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// A stand-in node: serves back whatever transactions it was seeded
+    /// with, and tracks which outpoints `broadcast` has since spent. Good
+    /// enough to drive `claim_swap`/`refund_swap` without a real regtest
+    /// node behind `ChainBackend`.
+    struct MockBackend {
+        txs: HashMap<Txid, Transaction>,
+        spent: RefCell<HashMap<(Txid, u32), bool>>,
+        block_count: u32,
+    }
+
+    impl ChainBackend for MockBackend {
+        fn get_transaction(&self, txid: &Txid) -> Result<Transaction, GlyphError> {
+            self.txs.get(txid).cloned()
+                .ok_or_else(|| GlyphError::InvalidTransaction("unknown txid in MockBackend".to_string()))
+        }
+
+        fn get_tx_out(&self, txid: &Txid, vout: u32) -> Result<Option<TxOut>, GlyphError> {
+            let spent = *self.spent.borrow().get(&(*txid, vout)).unwrap_or(&false);
+            if spent {
+                return Ok(None);
+            }
+            Ok(self.txs.get(txid).and_then(|tx| tx.output.get(vout as usize).cloned()))
+        }
+
+        fn get_block_count(&self) -> Result<u32, GlyphError> { Ok(self.block_count) }
+        fn get_block_transactions(&self, _height: u32) -> Result<Vec<Transaction>, GlyphError> { Ok(vec![]) }
+        fn get_mempool_transactions(&self) -> Result<Vec<Transaction>, GlyphError> { Ok(vec![]) }
+
+        fn broadcast(&self, raw_tx: &[u8]) -> Result<Txid, GlyphError> {
+            let tx: Transaction = bitcoin::consensus::deserialize(raw_tx)
+                .map_err(|e| GlyphError::InvalidTransaction(e.to_string()))?;
+            for input in &tx.input {
+                self.spent.borrow_mut().insert((input.previous_output.txid, input.previous_output.vout), true);
+            }
+            Ok(tx.txid())
+        }
+
+        fn new_change_address(&self) -> Result<String, GlyphError> { unimplemented!("not exercised by these tests") }
+        fn pubkey_for_address(&self, _address: &str) -> Result<PublicKey, GlyphError> { unimplemented!("not exercised by these tests") }
+    }
+
+    /// Only ever able to sign a leaf whose script embeds `own_xonly` — close
+    /// enough to a real wallet's behavior (it can't produce a signature for
+    /// a key it doesn't hold) to catch `claim_swap`/`refund_swap` building
+    /// a witness around the wrong party's key, which is exactly the bug
+    /// this module exists to guard against.
+    struct MockSigner {
+        own_xonly: [u8; 32],
+    }
+
+    impl Signer for MockSigner {
+        fn sign_transaction(&self, tx: &Transaction) -> Result<Vec<u8>, GlyphError> {
+            for input in &tx.input {
+                let leaf_script = input.witness.iter().rev().nth(1)
+                    .ok_or_else(|| GlyphError::InvalidTransaction("witness has no leaf script".to_string()))?;
+                if !leaf_script.windows(32).any(|w| w == self.own_xonly) {
+                    return Err(GlyphError::InvalidTransaction("MockSigner holds no key for this leaf".to_string()));
+                }
+            }
+            Ok(bitcoin::consensus::serialize(tx))
+        }
+
+        fn combine_psbt(&self, _psbts: &[String]) -> Result<String, GlyphError> { unimplemented!("not exercised by these tests") }
+        fn finalize_psbt(&self, _psbt_base64: &str) -> Result<Vec<u8>, GlyphError> { unimplemented!("not exercised by these tests") }
+        fn list_unspent(&self) -> Result<Vec<bitcoincore_rpc::json::ListUnspentResultEntry>, GlyphError> { Ok(vec![]) }
+        fn export_secret_for_pubkey(&self, _pubkey: &PublicKey, _network: Network) -> Result<SecretKey, GlyphError> { unimplemented!("not exercised by these tests") }
+    }
+
+    const TEST_DEST_ADDRESS: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+    fn test_pubkey(byte: u8) -> PublicKey {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+        PublicKey { compressed: true, key: secp256k1::PublicKey::from_secret_key(&secp, &secret_key) }
+    }
+
+    /// Builds a one-input, one-output transaction paying `value` to
+    /// `script_pubkey` — enough to stand in for an HTLC funding tx, since
+    /// `claim_swap`/`refund_swap` only ever look at the named output.
+    fn funding_tx(script_pubkey: Script, value: u64) -> Transaction {
+        let dummy_prevout = Txid::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(dummy_prevout, 0),
+                script_sig: Script::new(),
+                sequence: 0xFFFFFFFF,
+                witness: vec![],
+            }],
+            output: vec![TxOut { value, script_pubkey }],
+        }
+    }
+
+    /// A `create_htlc_script` call doesn't touch `backend`/`signer`, so any
+    /// throwaway instance can build the leaf scripts used to seed a mock
+    /// backend's transactions before the real test protocol exists.
+    fn script_builder() -> GlyphProtocol {
+        GlyphProtocol {
+            network: Network::Regtest,
+            backend: Box::new(MockBackend { txs: HashMap::new(), spent: RefCell::new(HashMap::new()), block_count: 0 }),
+            signer: Box::new(MockSigner { own_xonly: [0u8; 32] }),
+            base_offset: 1,
+            swap_store_path: "unused.store".to_string(),
+        }
+    }
+
+    fn test_protocol(backend: MockBackend, signer: MockSigner, swap_store_path: &str) -> GlyphProtocol {
+        let _ = std::fs::remove_file(swap_store_path);
+        GlyphProtocol {
+            network: Network::Regtest,
+            backend: Box::new(backend),
+            signer: Box::new(signer),
+            base_offset: 1,
+            swap_store_path: swap_store_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn claim_swap_spends_the_peer_leg_with_our_own_key() {
+        let preimage = [7u8; 32];
+        let secret_hash = hash160::Hash::hash(&preimage).into_inner().to_vec();
+        let alice_pubkey = test_pubkey(1); // us: receiver on the peer leg
+        let bob_pubkey = test_pubkey(2); // counterparty: sender/refund on the peer leg
+        let peer_timelock = 700_000;
+
+        let peer_script = script_builder().create_htlc_script(&alice_pubkey, &bob_pubkey, &secret_hash, peer_timelock).unwrap();
+        let peer_tx = funding_tx(peer_script, 50_000);
+        let peer_txid = peer_tx.txid();
+        let mut txs = HashMap::new();
+        txs.insert(peer_txid, peer_tx);
+
+        let protocol = test_protocol(
+            MockBackend { txs, spent: RefCell::new(HashMap::new()), block_count: 600_000 },
+            MockSigner { own_xonly: alice_pubkey.key.serialize()[1..33].try_into().unwrap() },
+            "test_claim_swap_spends_the_peer_leg.store",
+        );
+
+        protocol.persist_swap(&SwapRecord {
+            role: SwapRole::Initiator,
+            htlc_txid: "1111111111111111111111111111111111111111111111111111111111111111".to_string(),
+            vout: 0,
+            amount: 1_000,
+            secret_hash: secret_hash.clone(),
+            preimage: None,
+            timelock: 800_000,
+            counterparty_pubkey: bob_pubkey.to_string(),
+            own_pubkey: alice_pubkey.to_string(),
+            destination_address: TEST_DEST_ADDRESS.to_string(),
+            peer_htlc_txid: Some(peer_txid.to_string()),
+            peer_vout: Some(0),
+            peer_timelock: Some(peer_timelock),
+        }).unwrap();
+
+        let result = protocol.claim_swap(&peer_txid.to_string(), 0, &preimage, TEST_DEST_ADDRESS);
+        assert!(result.is_ok(), "claim_swap should succeed spending the peer leg with our own key: {:?}", result.err());
+    }
+
+    #[test]
+    fn claim_swap_rejects_wrong_preimage() {
+        let preimage = [7u8; 32];
+        let wrong_preimage = [9u8; 32];
+        let secret_hash = hash160::Hash::hash(&preimage).into_inner().to_vec();
+        let alice_pubkey = test_pubkey(1);
+        let bob_pubkey = test_pubkey(2);
+        let peer_timelock = 700_000;
+
+        let peer_script = script_builder().create_htlc_script(&alice_pubkey, &bob_pubkey, &secret_hash, peer_timelock).unwrap();
+        let peer_tx = funding_tx(peer_script, 50_000);
+        let peer_txid = peer_tx.txid();
+        let mut txs = HashMap::new();
+        txs.insert(peer_txid, peer_tx);
+
+        let protocol = test_protocol(
+            MockBackend { txs, spent: RefCell::new(HashMap::new()), block_count: 600_000 },
+            MockSigner { own_xonly: alice_pubkey.key.serialize()[1..33].try_into().unwrap() },
+            "test_claim_swap_rejects_wrong_preimage.store",
+        );
+
+        protocol.persist_swap(&SwapRecord {
+            role: SwapRole::Initiator,
+            htlc_txid: "2222222222222222222222222222222222222222222222222222222222222222".to_string(),
+            vout: 0,
+            amount: 1_000,
+            secret_hash,
+            preimage: None,
+            timelock: 800_000,
+            counterparty_pubkey: bob_pubkey.to_string(),
+            own_pubkey: alice_pubkey.to_string(),
+            destination_address: TEST_DEST_ADDRESS.to_string(),
+            peer_htlc_txid: Some(peer_txid.to_string()),
+            peer_vout: Some(0),
+            peer_timelock: Some(peer_timelock),
+        }).unwrap();
+
+        let result = protocol.claim_swap(&peer_txid.to_string(), 0, &wrong_preimage, TEST_DEST_ADDRESS);
+        assert!(result.is_err(), "claim_swap must reject a preimage that doesn't hash to the HTLC's secret_hash");
+    }
+
+    #[test]
+    fn refund_swap_reclaims_our_own_leg_after_timelock() {
+        let preimage = [7u8; 32];
+        let secret_hash = hash160::Hash::hash(&preimage).into_inner().to_vec();
+        let alice_pubkey = test_pubkey(1); // us: sender/refund on our own leg
+        let bob_pubkey = test_pubkey(2); // counterparty: receiver on our own leg
+        let own_timelock = 600_000;
+
+        // Built the same way `initiate_swap` builds it: receiver is the
+        // counterparty, sender is us.
+        let own_script = script_builder().create_htlc_script(&bob_pubkey, &alice_pubkey, &secret_hash, own_timelock).unwrap();
+        let own_tx = funding_tx(own_script, 50_000);
+        let own_txid = own_tx.txid();
+        let mut txs = HashMap::new();
+        txs.insert(own_txid, own_tx);
+
+        let protocol = test_protocol(
+            MockBackend { txs, spent: RefCell::new(HashMap::new()), block_count: 650_000 }, // past the timelock
+            MockSigner { own_xonly: alice_pubkey.key.serialize()[1..33].try_into().unwrap() },
+            "test_refund_swap_reclaims_our_own_leg.store",
+        );
+
+        protocol.persist_swap(&SwapRecord {
+            role: SwapRole::Initiator,
+            htlc_txid: own_txid.to_string(),
+            vout: 0,
+            amount: 1_000,
+            secret_hash,
+            preimage: Some(preimage.to_vec()),
+            timelock: own_timelock,
+            counterparty_pubkey: bob_pubkey.to_string(),
+            own_pubkey: alice_pubkey.to_string(),
+            destination_address: TEST_DEST_ADDRESS.to_string(),
+            peer_htlc_txid: None,
+            peer_vout: None,
+            peer_timelock: None,
+        }).unwrap();
+
+        let result = protocol.refund_swap(&own_txid.to_string(), 0, TEST_DEST_ADDRESS);
+        assert!(result.is_ok(), "refund_swap should succeed reclaiming our own leg past the timelock: {:?}", result.err());
+    }
+
+    #[test]
+    fn multisig_config_rejects_under_threshold_signers() {
+        let config = MultisigConfig {
+            threshold: 3,
+            signer_pubkeys: vec![test_pubkey(1), test_pubkey(2)],
+        };
+        assert!(config.validate().is_err(), "a 3-of-2 config is never satisfiable and must be rejected");
+    }
+
+    #[test]
+    fn multisig_config_accepts_two_of_three() {
+        let config = MultisigConfig {
+            threshold: 2,
+            signer_pubkeys: vec![test_pubkey(1), test_pubkey(2), test_pubkey(3)],
+        };
+        assert!(config.validate().is_ok());
+    }
+}